@@ -0,0 +1,75 @@
+use crate::{
+    bot::{
+        messages::downloader::audio_only_handler,
+        modules::{Owner, cobalt::CobaltSettings},
+    },
+    core::{config::Config, db::schemas::settings::Settings, services::cobalt::DownloadResult},
+    errors::MyError,
+};
+use std::sync::Arc;
+use teloxide::{
+    prelude::*,
+    types::{CallbackQuery, ReplyParameters},
+};
+
+pub async fn handle_cobalt_audio(
+    bot: Bot,
+    q: CallbackQuery,
+    config: Arc<Config>,
+) -> Result<(), MyError> {
+    let Some(url_hash) = q.data.as_deref().and_then(|data| data.strip_prefix("cobalt_audio:"))
+    else {
+        return Ok(());
+    };
+    let Some(message) = q.message.as_ref().and_then(|message| message.regular_message()) else {
+        bot.answer_callback_query(q.id).await?;
+        return Ok(());
+    };
+
+    let cache_key = format!("cobalt_cache:{}", url_hash);
+    let Ok(Some(
+        DownloadResult::Video { original_url, .. } | DownloadResult::Audio { original_url, .. },
+    )) = config.get_redis_client().get::<DownloadResult>(&cache_key).await
+    else {
+        bot.answer_callback_query(q.id)
+            .text("Извините, ссылка на это видео уже устарела.")
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    };
+
+    bot.answer_callback_query(q.id.clone()).text("⏳ Загружаю аудио...").await?;
+
+    let owner = Owner {
+        id: message.chat.id.to_string(),
+        r#type: (if message.chat.is_private() { "user" } else { "group" }).to_string(),
+    };
+    let settings: CobaltSettings = Settings::get_module_settings(&owner, "cobalt").await?;
+
+    let sent = audio_only_handler(
+        &bot,
+        &config,
+        message.chat.id,
+        message.id,
+        &settings,
+        &original_url,
+    )
+    .await;
+
+    match sent {
+        Ok(true) => {}
+        Ok(false) => {
+            bot.send_message(message.chat.id, "❌ У этого видео нет отдельной аудиодорожки.")
+                .reply_parameters(ReplyParameters::new(message.id))
+                .await?;
+        }
+        Err(e) => {
+            log::error!("Failed to send audio for {}: {}", original_url, e);
+            bot.send_message(message.chat.id, "❌ Не удалось получить аудио.")
+                .reply_parameters(ReplyParameters::new(message.id))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
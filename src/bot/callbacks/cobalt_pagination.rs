@@ -1,13 +1,19 @@
 use crate::{
     bot::keyboards::cobalt::make_photo_pagination_keyboard,
-    core::{config::Config, services::cobalt::DownloadResult},
+    core::{
+        config::Config,
+        services::{
+            cobalt::{DownloadResult, MediaItem, MediaKind},
+            uploader::{gallery_cache_key, upload_video_to_storage},
+        },
+    },
     errors::MyError,
 };
 use std::sync::Arc;
 use teloxide::{
     ApiError, RequestError,
     prelude::*,
-    types::{CallbackQuery, InputFile, InputMedia, InputMediaPhoto},
+    types::{CallbackQuery, InputFile, InputMedia, InputMediaPhoto, InputMediaVideo},
 };
 
 struct PagingData<'a> {
@@ -32,6 +38,27 @@ impl<'a> PagingData<'a> {
     }
 }
 
+// videos are swapped in by file id, an inline message can't take anything else
+pub async fn gallery_item_media(
+    config: &Config,
+    original_url: &str,
+    item: &MediaItem,
+    index: usize,
+) -> Result<InputMedia, MyError> {
+    match item.kind {
+        MediaKind::Photo => Ok(InputMedia::Photo(InputMediaPhoto::new(InputFile::url(
+            item.url.parse()?,
+        )))),
+        MediaKind::Video => {
+            let cache_key = gallery_cache_key(original_url, index);
+            let file_id = upload_video_to_storage(config, &item.url, &cache_key).await?;
+            Ok(InputMedia::Video(
+                InputMediaVideo::new(InputFile::file_id(file_id.into())).supports_streaming(true),
+            ))
+        }
+    }
+}
+
 pub async fn handle_cobalt_pagination(
     bot: Bot,
     q: CallbackQuery,
@@ -60,8 +87,11 @@ pub async fn handle_cobalt_pagination(
 
     let cache_key = format!("cobalt_cache:{}", paging_data.url_hash);
     let redis = config.get_redis_client();
-    let Ok(Some(DownloadResult::Photos { urls, original_url })) = redis.get(&cache_key).await
-    else {
+    let cached = redis.get::<DownloadResult>(&cache_key).await.ok().flatten();
+    let Some((items, original_url)) = cached.and_then(|media| {
+        let items = media.gallery_items()?;
+        Some((items, media.original_url().to_string()))
+    }) else {
         bot.answer_callback_query(q.id)
             .text("Извините, срок хранения этих фото истёк.")
             .show_alert(true)
@@ -69,20 +99,41 @@ pub async fn handle_cobalt_pagination(
         return Ok(());
     };
 
-    let Some(photo_url_str) = urls.get(paging_data.index) else {
+    let Some(item) = items.get(paging_data.index) else {
         log::error!(
             "Pagination index {} is out of bounds for len {}",
             paging_data.index,
-            urls.len()
+            items.len()
         );
         return Ok(());
     };
-    let Ok(url) = photo_url_str.parse() else {
-        log::error!("Failed to parse photo URL: {}", photo_url_str);
-        return Ok(());
+
+    // a video may take longer to upload than telegram waits for the answer, so it comes first
+    let answered_early = matches!(item.kind, MediaKind::Video);
+    if answered_early {
+        bot.answer_callback_query(q.id.clone())
+            .text("⏳ Загружаю видео...")
+            .await?;
+    }
+
+    let media = match gallery_item_media(&config, &original_url, item, paging_data.index).await {
+        Ok(media) => media,
+        Err(e) => {
+            log::error!(
+                "Failed to prepare carousel item {}: {}",
+                paging_data.index,
+                e
+            );
+            if !answered_early {
+                bot.answer_callback_query(q.id)
+                    .text("Не удалось загрузить этот элемент.")
+                    .show_alert(true)
+                    .await?;
+            }
+            return Ok(());
+        }
     };
 
-    let media = InputMedia::Photo(InputMediaPhoto::new(InputFile::url(url)));
     let keyboard = make_photo_pagination_keyboard(
         paging_data.url_hash,
         paging_data.index,
@@ -109,12 +160,17 @@ pub async fn handle_cobalt_pagination(
         && !matches!(e, RequestError::Api(ApiError::MessageNotModified))
     {
         log::error!("Failed to edit message for pagination: {}", e);
-        bot.answer_callback_query(q.id.clone())
-            .text("Не удалось обновить фото.")
-            .await?;
+        if !answered_early {
+            bot.answer_callback_query(q.id)
+                .text("Не удалось обновить медиа.")
+                .await?;
+        }
+        return Ok(());
     }
 
-    bot.answer_callback_query(q.id).await?;
+    if !answered_early {
+        bot.answer_callback_query(q.id).await?;
+    }
 
     Ok(())
 }
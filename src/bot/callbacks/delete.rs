@@ -3,8 +3,8 @@ use crate::{
     core::{
         config::Config,
         db::schemas::{
-            group::Group as GroupSchema, settings::Settings as SettingsSchema,
-            user::User as UserSchema,
+            alert::Alert as AlertSchema, group::Group as GroupSchema, settings::Settings as SettingsSchema,
+            transcript::Transcript as TranscriptSchema, user::User as UserSchema,
         },
         services::speech_recognition::back_handler,
     },
@@ -231,9 +231,11 @@ pub async fn handle_delete_data_confirmation(
 
             SettingsSchema::delete_one(doc! { "owner_id": &owner.id, "owner_type": &owner.r#type })
                 .await?;
+            AlertSchema::delete(doc! { "chat_id": &owner.id }).await?;
 
             if owner.r#type == "user" {
                 UserSchema::delete_one(doc! { "user_id": &owner.id }).await?;
+                TranscriptSchema::delete(doc! { "user_id": &owner.id }).await?;
             } else if owner.r#type == "group" {
                 GroupSchema::delete_one(doc! { "group_id": &owner.id }).await?;
             }
@@ -1,11 +1,12 @@
 use crate::{
     bot::{
         callbacks::{
-            cobalt_pagination::handle_cobalt_pagination,
+            cobalt_audio::handle_cobalt_audio, cobalt_pagination::handle_cobalt_pagination,
             delete::{
                 handle_delete_confirmation, handle_delete_data, handle_delete_data_confirmation,
                 handle_delete_request,
             },
+            transcripts::handle_transcripts_callback,
             translate::handle_translate_callback,
             whisper::handle_whisper_callback,
         },
@@ -14,7 +15,10 @@ use crate::{
     },
     core::{
         config::Config,
-        services::speech_recognition::{back_handler, pagination_handler, summarization_handler},
+        services::speech_recognition::{
+            back_handler, export_handler, pagination_handler, summarization_handler,
+            summary_style_handler, translate_transcript_handler,
+        },
     },
     errors::MyError,
 };
@@ -27,8 +31,10 @@ use teloxide::{
 };
 use crate::core::services::speech_recognition::retry_speech_handler;
 
+pub mod cobalt_audio;
 pub mod cobalt_pagination;
 pub mod delete;
+pub mod transcripts;
 pub mod translate;
 pub mod whisper;
 
@@ -53,19 +59,32 @@ enum CallbackAction<'a> {
         commander_id: u64,
     },
     CobaltPagination,
+    CobaltAudio,
     DeleteDataConfirmation,
     DeleteMessage,
     DeleteConfirmation,
     Summarize {
         user_id: u64,
     },
+    SummaryStyle {
+        style: &'a str,
+        user_id: u64,
+    },
     RetrySpeech {
         message_id: i32,
         user_id: u64,
-        action_type: &'a str, // "transcribe" or "summarize"
+        action_type: &'a str, // "transcribe" or "summarize_{style}"
         attempt: u32,
     },
+    SpeechExport {
+        format: &'a str,
+        user_id: u64,
+    },
+    SpeechTranslate {
+        user_id: u64,
+    },
     SpeechPage,
+    Transcripts,
     BackToFull,
     Whisper,
     Translate,
@@ -150,6 +169,12 @@ fn parse_callback_data(data: &'_ str) -> Option<CallbackAction<'_>> {
     {
         return Some(CallbackAction::Summarize { user_id: author_id });
     }
+    if let Some(rest) = data.strip_prefix("summarize_style:")
+        && let Some((style, user_id)) = rest.rsplit_once(':')
+        && let Ok(user_id) = user_id.parse()
+    {
+        return Some(CallbackAction::SummaryStyle { style, user_id });
+    }
     if let Some(rest) = data.strip_prefix("retry_speech:") {
         let parts: Vec<_> = rest.splitn(4, ':').collect();
         if parts.len() == 4
@@ -165,9 +190,23 @@ fn parse_callback_data(data: &'_ str) -> Option<CallbackAction<'_>> {
             });
         }
     }
+    if let Some(rest) = data.strip_prefix("speech_export:")
+        && let Some((format, user_id)) = rest.split_once(':')
+        && let Ok(user_id) = user_id.parse()
+    {
+        return Some(CallbackAction::SpeechExport { format, user_id });
+    }
+    if let Some(user_id) = data.strip_prefix("speech_translate:")
+        && let Ok(user_id) = user_id.parse()
+    {
+        return Some(CallbackAction::SpeechTranslate { user_id });
+    }
     if data.starts_with("speech:page:") {
         return Some(CallbackAction::SpeechPage);
     }
+    if data.starts_with("transcripts:") {
+        return Some(CallbackAction::Transcripts);
+    }
     if data.starts_with("back_to_full") {
         return Some(CallbackAction::BackToFull);
     }
@@ -180,6 +219,9 @@ fn parse_callback_data(data: &'_ str) -> Option<CallbackAction<'_>> {
     if data.starts_with("cobalt:") {
         return Some(CallbackAction::CobaltPagination);
     }
+    if data.starts_with("cobalt_audio:") {
+        return Some(CallbackAction::CobaltAudio);
+    }
 
     None
 }
@@ -295,6 +337,7 @@ pub async fn callback_query_handlers(bot: Bot, q: CallbackQuery) -> Result<(), M
             handle_delete_data(bot, q).await?
         }
         Some(CallbackAction::CobaltPagination) => handle_cobalt_pagination(bot, q, config).await?,
+        Some(CallbackAction::CobaltAudio) => handle_cobalt_audio(bot, q, config).await?,
         Some(CallbackAction::DeleteDataConfirmation) => {
             handle_delete_data_confirmation(bot, q).await?
         }
@@ -303,10 +346,20 @@ pub async fn callback_query_handlers(bot: Bot, q: CallbackQuery) -> Result<(), M
             handle_delete_confirmation(bot, q, &config).await?
         }
         Some(CallbackAction::Summarize {user_id}) => summarization_handler(bot, q, &config, user_id).await?,
+        Some(CallbackAction::SummaryStyle { style, user_id }) => {
+            summary_style_handler(bot, q, &config, style, user_id).await?
+        }
         Some(CallbackAction::RetrySpeech { message_id, user_id, action_type, attempt }) => {
             retry_speech_handler(bot, q.clone(), &config, message_id, user_id, action_type, attempt).await?
         }
+        Some(CallbackAction::SpeechExport { format, user_id }) => {
+            export_handler(bot, q, &config, format, user_id).await?
+        }
+        Some(CallbackAction::SpeechTranslate { user_id }) => {
+            translate_transcript_handler(bot, q, &config, user_id).await?
+        }
         Some(CallbackAction::SpeechPage) => pagination_handler(bot, q, &config).await?,
+        Some(CallbackAction::Transcripts) => handle_transcripts_callback(bot, q, &config).await?,
         Some(CallbackAction::BackToFull) => back_handler(bot, q, &config).await?,
         Some(CallbackAction::Whisper) => handle_whisper_callback(bot, q, &config).await?,
         Some(CallbackAction::Translate) => handle_translate_callback(bot, q, &config).await?,
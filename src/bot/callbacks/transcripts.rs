@@ -0,0 +1,93 @@
+use crate::{
+    bot::commands::transcripts::{render_transcript_view, render_transcripts_list},
+    core::{config::Config, db::schemas::transcript::Transcript},
+    errors::MyError,
+};
+use mongodb::bson::{doc, oid::ObjectId};
+use oximod::Model;
+use teloxide::{
+    ApiError, RequestError,
+    prelude::*,
+    types::ParseMode,
+};
+
+pub async fn handle_transcripts_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    config: &Config,
+) -> Result<(), MyError> {
+    let Some(data) = q.data.as_ref() else {
+        return Ok(());
+    };
+    let Some(message) = q.message.as_ref().and_then(|m| m.regular_message()) else {
+        return Ok(());
+    };
+
+    let parts: Vec<&str> = data.split(':').collect();
+    let Some(user_id) = parts.last().and_then(|id| id.parse::<u64>().ok()) else {
+        return Ok(());
+    };
+
+    if q.from.id.0 != user_id {
+        bot.answer_callback_query(q.id.clone())
+            .text("❌ Это не ваша история.")
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    }
+
+    let user_key = user_id.to_string();
+    let (text, keyboard) = match parts.as_slice() {
+        [_, "page", page, _] => {
+            let query: String = config
+                .get_redis_client()
+                .get(&format!("transcripts_query:{}", user_id))
+                .await?
+                .unwrap_or_default();
+            render_transcripts_list(user_id, &query, page.parse().unwrap_or(0)).await?
+        }
+        [_, "open", id, _] | [_, "view", id, _, _] => {
+            let page = match parts.as_slice() {
+                [_, "view", _, page, _] => page.parse().unwrap_or(0),
+                _ => 0,
+            };
+            let Some(transcript) = Transcript::find_for_user(&user_key, id).await? else {
+                bot.answer_callback_query(q.id.clone())
+                    .text("❌ Расшифровка не найдена.")
+                    .show_alert(true)
+                    .await?;
+                return Ok(());
+            };
+            render_transcript_view(&transcript, page, user_id)
+        }
+        [_, "del", id, _] => {
+            if let Ok(object_id) = ObjectId::parse_str(id) {
+                Transcript::delete_one(doc! { "_id": object_id, "user_id": &user_key }).await?;
+            }
+            bot.answer_callback_query(q.id.clone())
+                .text("🗑️ Расшифровка удалена.")
+                .await?;
+            render_transcripts_list(user_id, "", 0).await?
+        }
+        _ => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            return Ok(());
+        }
+    };
+
+    let edit_result = bot
+        .edit_message_text(message.chat.id, message.id, text)
+        .parse_mode(ParseMode::Html)
+        .reply_markup(keyboard)
+        .await;
+
+    if let Err(e) = edit_result
+        && !matches!(e, RequestError::Api(ApiError::MessageNotModified))
+    {
+        return Err(e.into());
+    }
+
+    bot.answer_callback_query(q.id).await.ok();
+
+    Ok(())
+}
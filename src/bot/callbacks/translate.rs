@@ -5,7 +5,10 @@ use crate::{
     },
     core::{
         config::Config,
-        services::translation::{SUPPORTED_LANGUAGES, normalize_language_code},
+        services::{
+            speech_recognition::get_transcript_for_message,
+            translation::{SUPPORTED_LANGUAGES, normalize_language_code},
+        },
     },
     errors::MyError,
     util::{
@@ -101,6 +104,7 @@ async fn handle_translation_pagination(
             .set_callback_formatter(move |p| format!("tr:page:{}:{}", translation_id, p))
             .add_bottom_row(vec![switch_lang_button, delete_button])
             .build();
+        let keyboard = add_transcript_back_button(config, message, keyboard).await?;
 
         let new_text = format!(
             "<blockquote>{}</blockquote>",
@@ -123,6 +127,19 @@ async fn handle_translation_pagination(
     Ok(())
 }
 
+async fn add_transcript_back_button(
+    config: &Config,
+    message: &Message,
+    mut keyboard: InlineKeyboardMarkup,
+) -> Result<InlineKeyboardMarkup, MyError> {
+    if get_transcript_for_message(config, message.chat.id, message.id).await?.is_some() {
+        keyboard
+            .inline_keyboard
+            .push(vec![InlineKeyboardButton::callback("⬅️ Назад", "back_to_full")]);
+    }
+    Ok(keyboard)
+}
+
 async fn handle_language_menu_pagination(
     bot: Bot,
     message: &Message,
@@ -225,6 +242,7 @@ async fn handle_language_selection(
         } else {
             keyboard.inline_keyboard.push(vec![switch_lang_button]);
         }
+        let keyboard = add_transcript_back_button(config, message, keyboard).await?;
         bot.edit_message_text(message.chat.id, message.id, response)
             .parse_mode(ParseMode::Html)
             .reply_markup(keyboard)
@@ -253,6 +271,8 @@ async fn handle_language_selection(
             .add_bottom_row(vec![switch_lang_button, delete_button])
             .build();
 
+        let keyboard = add_transcript_back_button(config, message, keyboard).await?;
+
         let response_text = format!("<blockquote>{}</blockquote>", escape(&display_pages[0]));
         bot.edit_message_text(message.chat.id, message.id, response_text)
             .parse_mode(ParseMode::Html)
@@ -270,12 +290,19 @@ async fn handle_show_languages(
     config: &Config,
 ) -> Result<(), MyError> {
     if let Some(original_message) = message.reply_to_message() {
+        // voice messages have no text, the transcript lives in the speech cache instead
+        let transcript = get_transcript_for_message(config, message.chat.id, message.id)
+            .await?
+            .map(|entry| entry.full_text);
+
         if let Some(text) = original_message
             .text()
             .or_else(|| original_message.caption())
+            .map(|text| text.to_string())
+            .or(transcript)
         {
             let job = TranslateJob {
-                text: text.to_string(),
+                text,
                 user_id: user.id.0,
             };
             let redis_key_job = format!("translate_job:{}", user.id);
@@ -1,7 +1,9 @@
 use crate::{
     bot::commands::{
-        settings::settings_command_handler, speech_recognition::speech_recognition_handler,
-        start::start_handler, translate::translate_handler,
+        alert::alert_handler, alias::alias_handler, chart::chart_handler,
+        currencies::reload_currencies_handler, settings::settings_command_handler,
+        speech_recognition::speech_recognition_handler, start::start_handler,
+        transcripts::transcripts_handler, translate::translate_handler,
     },
     core::config::Config,
     errors::MyError,
@@ -18,6 +20,11 @@ pub async fn command_handlers(bot: Bot, message: Message, cmd: Command) -> Resul
             Command::Translate(arg) => translate_handler(bot, &message, &config, arg).await,
             Command::SpeechRecognition => speech_recognition_handler(bot, message, &config).await,
             Command::Settings => settings_command_handler(bot, message).await,
+            Command::Transcripts(query) => transcripts_handler(bot, message, &config, query).await,
+            Command::Chart(args) => chart_handler(bot, message, &config, args).await,
+            Command::Alert(args) => alert_handler(bot, message, &config, args).await,
+            Command::Alias(args) => alias_handler(bot, message, &config, args).await,
+            Command::ReloadCurrencies => reload_currencies_handler(bot, message, &config).await,
         }
     });
     Ok(())
@@ -0,0 +1,143 @@
+use crate::{
+    bot::modules::{Owner, currency::CurrencySettings},
+    core::{
+        config::Config,
+        db::schemas::{alert::Alert, settings::Settings},
+        services::alerts::{ALERT_USAGE, AlertCommand, describe_alert, parse_alert_command},
+    },
+    errors::MyError,
+    util::is_private_or_admin,
+};
+use teloxide::{
+    prelude::*,
+    types::{ParseMode, ReplyParameters},
+};
+
+async fn render_alerts_list(chat_id: &str) -> Result<String, MyError> {
+    let alerts = Alert::for_chat(chat_id).await?;
+    if alerts.is_empty() {
+        return Ok(format!("🔕 Уведомлений пока нет.\n\n{}", ALERT_USAGE));
+    }
+
+    let lines = alerts
+        .iter()
+        .enumerate()
+        .map(|(i, alert)| format!("{}. {}", i + 1, describe_alert(alert)))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(format!("<b>Уведомления о курсах</b>\n\n{}", lines))
+}
+
+pub async fn alert_handler(
+    bot: Bot,
+    msg: Message,
+    config: &Config,
+    args: String,
+) -> Result<(), MyError> {
+    let owner = Owner {
+        id: msg.chat.id.to_string(),
+        r#type: (if msg.chat.is_private() { "user" } else { "group" }).to_string(),
+    };
+    let settings: CurrencySettings = Settings::get_module_settings(&owner, "currency").await?;
+    if !settings.enabled {
+        bot.send_message(
+            msg.chat.id,
+            "❌ Модуль валют выключен в этом чате. Включить можно в /settings.",
+        )
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .await?;
+        return Ok(());
+    }
+
+    let Some(command) =
+        parse_alert_command(config.get_currency_converter(), &settings.aliases, &args)
+    else {
+        bot.send_message(msg.chat.id, ALERT_USAGE)
+            .parse_mode(ParseMode::Html)
+            .reply_parameters(ReplyParameters::new(msg.id))
+            .await?;
+        return Ok(());
+    };
+
+    if !matches!(command, AlertCommand::List) && !is_private_or_admin(&bot, &msg).await {
+        bot.send_message(msg.chat.id, "❌ Управлять уведомлениями могут только администраторы.")
+            .reply_parameters(ReplyParameters::new(msg.id))
+            .await?;
+        return Ok(());
+    }
+
+    let created_by = msg.from.as_ref().map(|user| user.id.0).unwrap_or_default().to_string();
+    let alerts_config = config.get_json_config().get_alerts_config();
+
+    let text = match command {
+        AlertCommand::List => render_alerts_list(&owner.id).await?,
+        AlertCommand::Delete(index) => {
+            let alerts = Alert::for_chat(&owner.id).await?;
+            match alerts.get(index - 1).and_then(|alert| alert.hex_id()) {
+                Some(id) if Alert::remove(&owner.id, &id).await? => {
+                    format!("🗑️ Уведомление удалено.\n\n{}", render_alerts_list(&owner.id).await?)
+                }
+                _ => "❌ Уведомление с таким номером не найдено.".to_string(),
+            }
+        }
+        AlertCommand::Digest(Some(minute)) => {
+            Alert::set_digest(&owner.id, &owner.r#type, &created_by, minute).await?;
+            format!(
+                "✅ Сводка курсов будет приходить каждый день в {:02}:{:02} (UTC{:+}).",
+                minute / 60,
+                minute % 60,
+                alerts_config.utc_offset_hours
+            )
+        }
+        AlertCommand::Digest(None) => {
+            let digest = Alert::for_chat(&owner.id)
+                .await?
+                .into_iter()
+                .find(|alert| alert.is_digest())
+                .and_then(|alert| alert.hex_id());
+            match digest {
+                Some(id) => {
+                    Alert::remove(&owner.id, &id).await?;
+                    "✅ Ежедневная сводка отключена.".to_string()
+                }
+                None => "ℹ️ Ежедневная сводка и так не включена.".to_string(),
+            }
+        }
+        AlertCommand::Threshold {
+            from,
+            to,
+            above,
+            value,
+        } => {
+            let count = Alert::for_chat(&owner.id).await?.len();
+            if count >= alerts_config.max_per_chat {
+                format!(
+                    "❌ Достигнут лимит уведомлений ({}). Удалите лишние: /alert list",
+                    alerts_config.max_per_chat
+                )
+            } else if let Err(e) = config.get_currency_converter().get_pair_rate(&from, &to).await {
+                // makes sure the pair can actually be priced before saving it
+                log::warn!("Can't price {}/{} for an alert: {}", from, to, e);
+                format!("❌ Не удалось получить курс {}/{}", from, to)
+            } else {
+                Alert::add_threshold(&owner.id, &owner.r#type, &created_by, &from, &to, above, value)
+                    .await?;
+                format!(
+                    "✅ Сообщу, когда {}/{} станет {} {}.",
+                    from,
+                    to,
+                    if above { "выше" } else { "ниже" },
+                    value
+                )
+            }
+        }
+    };
+
+    bot.send_message(msg.chat.id, text)
+        .parse_mode(ParseMode::Html)
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use crate::{
+    bot::modules::{Owner, currency::CurrencySettings},
+    core::{
+        config::Config, db::schemas::settings::Settings,
+        services::currency::converter::CurrencyAliases,
+    },
+    errors::MyError,
+    util::is_private_or_admin,
+};
+use teloxide::{
+    prelude::*,
+    types::{ParseMode, ReplyParameters},
+    utils::html,
+};
+
+const MAX_ALIASES: usize = 30;
+const MAX_ALIAS_LENGTH: usize = 24;
+
+const ALIAS_USAGE: &str = "Использование:\n\
+    <code>/alias зелень USD</code> — бот будет понимать «100 зелени» как доллары\n\
+    <code>/alias del зелень</code> — удалить название\n\
+    <code>/alias list</code> — список своих названий\n\n\
+    Название — одно слово из букв. Склонения нужно добавлять отдельно: «зелень», «зелени».";
+
+fn render_aliases(aliases: &CurrencyAliases) -> String {
+    if aliases.is_empty() {
+        return format!("🏷️ Своих названий пока нет.\n\n{}", ALIAS_USAGE);
+    }
+
+    let list = aliases
+        .iter()
+        .map(|(alias, code)| format!("• {} → {}", html::escape(alias), code))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("🏷️ <b>Свои названия валют</b>\n\n{}", list)
+}
+
+pub async fn alias_handler(
+    bot: Bot,
+    msg: Message,
+    config: &Config,
+    args: String,
+) -> Result<(), MyError> {
+    let owner = Owner {
+        id: msg.chat.id.to_string(),
+        r#type: (if msg.chat.is_private() { "user" } else { "group" }).to_string(),
+    };
+    let mut settings: CurrencySettings = Settings::get_module_settings(&owner, "currency").await?;
+    if !settings.enabled {
+        bot.send_message(
+            msg.chat.id,
+            "❌ Модуль валют выключен в этом чате. Включить можно в /settings.",
+        )
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .await?;
+        return Ok(());
+    }
+
+    let tokens: Vec<String> = args.split_whitespace().map(|t| t.to_lowercase()).collect();
+
+    let text = match tokens.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        [] | ["list" | "список"] => render_aliases(&settings.aliases),
+        _ if !is_private_or_admin(&bot, &msg).await => {
+            "❌ Менять названия валют могут только администраторы.".to_string()
+        }
+        ["del" | "delete" | "удалить", alias] => match settings.aliases.remove(*alias) {
+            Some(_) => {
+                let text = format!("🗑️ Название «{}» удалено.", html::escape(alias));
+                Settings::update_module_settings(&owner, "currency", settings).await?;
+                text
+            }
+            None => format!("❌ Названия «{}» нет в списке.", html::escape(alias)),
+        },
+        [alias, identifier] => {
+            let converter = config.get_currency_converter();
+            let valid_alias = (2..=MAX_ALIAS_LENGTH).contains(&alias.chars().count())
+                && alias.chars().all(char::is_alphabetic);
+
+            match converter.find_currency_info_by_identifier(identifier, &CurrencyAliases::new()) {
+                _ if !valid_alias => format!(
+                    "❌ Название должно быть одним словом из букв, от 2 до {} символов.",
+                    MAX_ALIAS_LENGTH
+                ),
+                _ if !settings.aliases.contains_key(*alias)
+                    && settings.aliases.len() >= MAX_ALIASES =>
+                {
+                    format!("❌ Можно добавить не больше {} названий.", MAX_ALIASES)
+                }
+                None => format!("❌ Не знаю валюту «{}».", html::escape(identifier)),
+                Some(info) => {
+                    let text = format!(
+                        "✅ Теперь «{}» — это {} {}.",
+                        html::escape(alias),
+                        info.flag,
+                        info.code
+                    );
+                    settings.aliases.insert(alias.to_string(), info.code.clone());
+                    Settings::update_module_settings(&owner, "currency", settings).await?;
+                    text
+                }
+            }
+        }
+        _ => ALIAS_USAGE.to_string(),
+    };
+
+    bot.send_message(msg.chat.id, text)
+        .parse_mode(ParseMode::Html)
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .await?;
+
+    Ok(())
+}
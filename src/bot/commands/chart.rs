@@ -0,0 +1,66 @@
+use crate::{
+    bot::{
+        keyboards::delete::delete_message_button,
+        modules::{Owner, currency::CurrencySettings},
+    },
+    core::{
+        config::Config,
+        db::schemas::settings::Settings,
+        services::currency::history::{CHART_USAGE, build_rate_chart, parse_chart_request},
+    },
+    errors::MyError,
+};
+use teloxide::{
+    prelude::*,
+    types::{InputFile, ParseMode, ReplyParameters},
+};
+
+pub async fn chart_handler(
+    bot: Bot,
+    msg: Message,
+    config: &Config,
+    args: String,
+) -> Result<(), MyError> {
+    let owner = Owner {
+        id: msg.chat.id.to_string(),
+        r#type: (if msg.chat.is_private() { "user" } else { "group" }).to_string(),
+    };
+    let settings: CurrencySettings = Settings::get_module_settings(&owner, "currency").await?;
+    if !settings.enabled {
+        bot.send_message(
+            msg.chat.id,
+            "❌ Модуль валют выключен в этом чате. Включить можно в /settings.",
+        )
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .await?;
+        return Ok(());
+    }
+
+    let Some(request) = parse_chart_request(config.get_currency_converter(), &settings.aliases, &args) else {
+        bot.send_message(msg.chat.id, CHART_USAGE)
+            .parse_mode(ParseMode::Html)
+            .reply_parameters(ReplyParameters::new(msg.id))
+            .await?;
+        return Ok(());
+    };
+
+    let Some(chart) = build_rate_chart(&request).await? else {
+        bot.send_message(
+            msg.chat.id,
+            "📉 Недостаточно данных для графика: история курсов за этот период ещё не накоплена.",
+        )
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .await?;
+        return Ok(());
+    };
+
+    let user_id = msg.from.as_ref().map(|user| user.id.0).unwrap_or_default();
+    bot.send_photo(msg.chat.id, InputFile::memory(chart.png).file_name("chart.png"))
+        .caption(chart.caption)
+        .parse_mode(ParseMode::Html)
+        .reply_markup(delete_message_button(user_id))
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use crate::{
+    core::{config::Config, services::currency::catalogue::reload_catalogue},
+    errors::MyError,
+};
+use teloxide::{
+    prelude::*,
+    types::{ParseMode, ReplyParameters},
+    utils::html,
+};
+
+const MAX_LISTED_ISSUES: usize = 30;
+
+pub async fn reload_currencies_handler(
+    bot: Bot,
+    msg: Message,
+    config: &Config,
+) -> Result<(), MyError> {
+    let Some(user) = msg.from.as_ref() else {
+        return Ok(());
+    };
+    if !config.is_id_in_owners(user.id.to_string()) {
+        return Ok(());
+    }
+
+    let text = match reload_catalogue() {
+        Ok(catalogue) => format!(
+            "✅ Каталог валют перезагружен: {} валют.",
+            catalogue.currencies.len()
+        ),
+        Err(issues) => {
+            let mut lines = issues
+                .iter()
+                .take(MAX_LISTED_ISSUES)
+                .map(|issue| format!("• {}", html::escape(issue)))
+                .collect::<Vec<_>>();
+            if issues.len() > MAX_LISTED_ISSUES {
+                lines.push(format!("… и ещё {}", issues.len() - MAX_LISTED_ISSUES));
+            }
+            format!(
+                "❌ Каталог не перезагружен, остаётся прежний. Ошибок: {}\n\n{}",
+                issues.len(),
+                lines.join("\n")
+            )
+        }
+    };
+
+    bot.send_message(msg.chat.id, text)
+        .parse_mode(ParseMode::Html)
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .await?;
+
+    Ok(())
+}
@@ -1,4 +1,9 @@
+pub mod alert;
+pub mod alias;
+pub mod chart;
+pub mod currencies;
 pub mod settings;
 pub mod speech_recognition;
 pub mod start;
+pub mod transcripts;
 pub mod translate;
@@ -1,4 +1,5 @@
 use crate::{
+    bot::modules::speech::get_chat_speech_settings,
     core::{config::Config, services::speech_recognition::transcription_handler},
     errors::MyError,
 };
@@ -9,6 +10,17 @@ pub async fn speech_recognition_handler(
     msg: Message,
     config: &Config,
 ) -> Result<(), MyError> {
+    if !get_chat_speech_settings(&msg.chat).await.enabled {
+        bot.send_message(
+            msg.chat.id,
+            "❌ Распознавание речи выключено в этом чате. Включить можно в /settings.",
+        )
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .await?;
+
+        return Ok(());
+    }
+
     let Some(message) = msg.reply_to_message() else {
         bot.send_message(msg.chat.id, "Ответьте на голосовое сообщение.")
             .reply_parameters(ReplyParameters::new(msg.id))
@@ -3,6 +3,7 @@ use crate::{
     core::{
         config::Config,
         db::schemas::{settings::Settings, user::User},
+        services::transcription::preprocess::PREPROCESS_STATS,
     },
     errors::MyError,
 };
@@ -49,6 +50,7 @@ pub async fn start_handler(
     let total_ram_mb = system_info.total_memory() / (1024 * 1024);
     let used_ram_mb = system_info.used_memory() / (1024 * 1024);
     let cpu_usage_percent = system_info.global_cpu_usage();
+    let audio_saved_mb = PREPROCESS_STATS.saved_bytes() as f64 / (1024.0 * 1024.0);
 
     let welcome_part = if is_new_user {
         "<b>Добро пожаловать!</b> 👋\n\n\
@@ -66,8 +68,15 @@ pub async fn start_handler(
         > Пинг API:    {} мс\n\
         > Нагрузка ЦП: {:.2}%\n\
         > ОЗУ:         {}/{} МБ\n\
+        > Экономия:    {:.1} МБ аудио ({} файлов)\n\
         </pre>",
-        version, api_ping, cpu_usage_percent, used_ram_mb, total_ram_mb
+        version,
+        api_ping,
+        cpu_usage_percent,
+        used_ram_mb,
+        total_ram_mb,
+        audio_saved_mb,
+        PREPROCESS_STATS.files()
     );
 
     let news_link_button =
@@ -0,0 +1,122 @@
+use crate::{
+    bot::{
+        keyboards::transcripts::{
+            TRANSCRIPTS_PER_PAGE, create_transcript_view_keyboard, create_transcripts_list_keyboard,
+        },
+        modules::speech::get_user_speech_settings,
+    },
+    core::{config::Config, db::schemas::transcript::Transcript},
+    errors::MyError,
+    util::{pack_paragraphs, split_text},
+};
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardMarkup, ParseMode, ReplyParameters},
+    utils::html,
+};
+
+pub async fn render_transcripts_list(
+    user_id: u64,
+    query: &str,
+    page: usize,
+) -> Result<(String, InlineKeyboardMarkup), MyError> {
+    let user_key = user_id.to_string();
+    let total = Transcript::count_matching(&user_key, query).await? as usize;
+
+    if total == 0 {
+        let text = if !query.is_empty() {
+            format!("🔍 По запросу «{}» ничего не найдено.", html::escape(query))
+        } else if !get_user_speech_settings(user_id).await.save_history {
+            "📚 История расшифровок выключена.\n\nВключить её можно в /settings → Распознавание речи.".to_string()
+        } else {
+            "📚 История расшифровок пока пуста.".to_string()
+        };
+        return Ok((text, InlineKeyboardMarkup::new(Vec::<Vec<_>>::new())));
+    }
+
+    let header = if query.is_empty() {
+        "📚 <b>Ваши расшифровки</b>".to_string()
+    } else {
+        format!("🔍 <b>Расшифровки по запросу</b> «{}»", html::escape(query))
+    };
+    let text = format!("{}\n\nНайдено: {}", header, total);
+
+    let page = page.min((total - 1) / TRANSCRIPTS_PER_PAGE);
+    let transcripts =
+        Transcript::search(&user_key, query, page * TRANSCRIPTS_PER_PAGE, TRANSCRIPTS_PER_PAGE)
+            .await?;
+
+    Ok((text, create_transcripts_list_keyboard(&transcripts, page, total, user_id)))
+}
+
+pub fn transcript_view_pages(transcript: &Transcript) -> Vec<String> {
+    if transcript.diarized {
+        let turns: Vec<String> = transcript.text.split("\n\n").map(str::to_string).collect();
+        pack_paragraphs(&turns, 3800)
+    } else {
+        split_text(&transcript.text, 3800)
+    }
+}
+
+pub fn render_transcript_view(
+    transcript: &Transcript,
+    page: usize,
+    user_id: u64,
+) -> (String, InlineKeyboardMarkup) {
+    let pages = transcript_view_pages(transcript);
+    let page = page.min(pages.len().saturating_sub(1));
+    let date = chrono::DateTime::from_timestamp(transcript.created_at, 0)
+        .map(|d| d.format("%d.%m.%Y %H:%M UTC").to_string())
+        .unwrap_or_default();
+
+    let text = format!(
+        "🗓 {}\n<blockquote expandable>{}</blockquote>",
+        date,
+        html::escape(pages.get(page).map(String::as_str).unwrap_or_default())
+    );
+    let keyboard = create_transcript_view_keyboard(
+        &transcript.hex_id().unwrap_or_default(),
+        page,
+        pages.len(),
+        user_id,
+    );
+
+    (text, keyboard)
+}
+
+pub async fn transcripts_handler(
+    bot: Bot,
+    msg: Message,
+    config: &Config,
+    query: String,
+) -> Result<(), MyError> {
+    if !msg.chat.is_private() {
+        bot.send_message(
+            msg.chat.id,
+            "📚 История расшифровок доступна только в личных сообщениях с ботом.",
+        )
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .await?;
+        return Ok(());
+    }
+
+    let Some(user) = msg.from.as_ref() else {
+        return Ok(());
+    };
+
+    // the query is too long for callback data, so paging reads it back from redis
+    let query = query.trim().to_string();
+    config
+        .get_redis_client()
+        .set(&format!("transcripts_query:{}", user.id.0), &query, 3600)
+        .await?;
+
+    let (text, keyboard) = render_transcripts_list(user.id.0, &query, 0).await?;
+    bot.send_message(msg.chat.id, text)
+        .parse_mode(ParseMode::Html)
+        .reply_markup(keyboard)
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .await?;
+
+    Ok(())
+}
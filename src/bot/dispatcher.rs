@@ -3,6 +3,7 @@ use crate::{
         callbacks::callback_query_handlers,
         commander::command_handlers,
         inlines::{
+            chart::{handle_chart_inline, is_chart_query},
             cobalter::{handle_cobalt_inline, handle_inline_video, is_query_url},
             currency::{handle_currency_inline, is_currency_query},
             whisper::{handle_whisper_inline, is_whisper_query},
@@ -15,6 +16,7 @@ use crate::{
     core::{
         config::Config,
         db::schemas::{settings::Settings, user::User as DBUser},
+        services::{alerts::run_alert_scheduler, cobalt_pool::run_cobalt_health_checks},
     },
     errors::MyError,
     util::enums::Command,
@@ -115,7 +117,8 @@ async fn are_any_inline_modules_enabled(q: InlineQuery) -> bool {
 
     if let Ok(settings) = Settings::get_or_create(&owner).await {
         for module in MOD_MANAGER.get_all_modules() {
-            if module.is_enabled(&owner).await
+            if module.is_inline()
+                && module.is_enabled(&owner).await
                 && let Some(settings_json) = settings.modules.get(module.key())
                 && let Ok(check) = serde_json::from_value::<EnabledCheck>(settings_json.clone())
                 && check.enabled
@@ -159,6 +162,7 @@ pub fn inline_query_handler() -> Handler<'static, Result<(), MyError>, DpHandler
         .branch(
             dptree::filter_async(is_user_registered)
                 .filter_async(are_any_inline_modules_enabled)
+                .branch(dptree::filter_async(is_chart_query).endpoint(handle_chart_inline))
                 .branch(dptree::filter_async(is_currency_query).endpoint(handle_currency_inline))
                 .branch(dptree::filter_async(is_query_url).endpoint(handle_cobalt_inline))
                 .branch(dptree::filter_async(is_translate_query).endpoint(handle_translate_inline))
@@ -181,7 +185,10 @@ async fn run_bot(config: Arc<Config>) -> Result<(), MyError> {
             Update::filter_message()
                 .branch(Message::filter_text().endpoint(handle_currency))
                 .branch(Message::filter_video_note().endpoint(handle_speech))
-                .branch(Message::filter_voice().endpoint(handle_speech)),
+                .branch(Message::filter_voice().endpoint(handle_speech))
+                .branch(Message::filter_audio().endpoint(handle_speech))
+                .branch(Message::filter_video().endpoint(handle_speech))
+                .branch(Message::filter_document().endpoint(handle_speech)),
         )
         .branch(Update::filter_callback_query().endpoint(callback_query_handlers))
         .branch(Update::filter_my_chat_member().endpoint(handle_bot_added))
@@ -212,6 +219,9 @@ async fn run_database(config: Arc<Config>) -> Result<(), MyError> {
 
 pub async fn run() -> Result<(), MyError> {
     let config = Arc::new(Config::new().await);
+    // never returns, so it lives outside the join and dies together with the runtime
+    tokio::spawn(run_alert_scheduler(config.clone()));
+    tokio::spawn(run_cobalt_health_checks(config.clone()));
     let _th = tokio::join!(run_database(config.clone()), run_bot(config.clone()));
     Ok(())
 }
@@ -0,0 +1,139 @@
+use crate::{
+    bot::modules::{Owner, currency::CurrencySettings},
+    core::{
+        config::Config,
+        db::schemas::settings::Settings,
+        services::currency::history::{build_rate_chart, parse_chart_request},
+    },
+    errors::MyError,
+};
+use log::warn;
+use std::sync::Arc;
+use teloxide::{
+    payloads::AnswerInlineQuerySetters,
+    prelude::*,
+    types::{
+        FileId, InlineQuery, InlineQueryResult, InlineQueryResultArticle,
+        InlineQueryResultCachedPhoto, InputFile, InputMessageContent, InputMessageContentText,
+        ParseMode,
+    },
+};
+use uuid::Uuid;
+
+// a day-long chart goes stale fast, a year-long one barely moves in a day
+fn chart_cache_ttl(days: u32) -> usize {
+    (days as usize * 60 * 60).clamp(10 * 60, 24 * 60 * 60)
+}
+
+const CHART_PREFIXES: [&str; 2] = ["chart ", "график "];
+
+fn strip_chart_prefix(query: &str) -> Option<&str> {
+    let lower = query.to_lowercase();
+    CHART_PREFIXES
+        .iter()
+        .find(|prefix| lower.starts_with(*prefix))
+        .map(|prefix| &query[prefix.len()..])
+}
+
+pub async fn is_chart_query(q: InlineQuery) -> bool {
+    if strip_chart_prefix(&q.query).is_none() {
+        return false;
+    }
+
+    let owner = Owner {
+        id: q.from.id.to_string(),
+        r#type: "user".to_string(),
+    };
+
+    match Settings::get_module_settings::<CurrencySettings>(&owner, "currency").await {
+        Ok(settings) => settings.enabled,
+        Err(_) => false,
+    }
+}
+
+fn chart_article(title: &str, text: &str) -> InlineQueryResult {
+    InlineQueryResult::Article(InlineQueryResultArticle::new(
+        Uuid::new_v4().to_string(),
+        title,
+        InputMessageContent::Text(InputMessageContentText::new(text)),
+    ))
+}
+
+pub async fn handle_chart_inline(
+    bot: Bot,
+    q: InlineQuery,
+    config: Arc<Config>,
+) -> Result<(), MyError> {
+    let args = strip_chart_prefix(&q.query).unwrap_or_default();
+
+    let owner = Owner {
+        id: q.from.id.to_string(),
+        r#type: "user".to_string(),
+    };
+    let settings: CurrencySettings = Settings::get_module_settings(&owner, "currency").await?;
+
+    let Some(request) = parse_chart_request(config.get_currency_converter(), &settings.aliases, args)
+    else {
+        let help = chart_article(
+            "Как построить график?",
+            "Напишите, например: chart usd uah 30d",
+        );
+        bot.answer_inline_query(q.id, vec![help]).cache_time(10).await?;
+        return Ok(());
+    };
+
+    // inline results can only show photos that already live on telegram servers
+    let cache_key = format!(
+        "chart_file:{}:{}:{}",
+        request.from.code, request.to.code, request.days
+    );
+    let redis = config.get_redis_client();
+    let cached: Option<(String, String)> = redis.get(&cache_key).await?;
+
+    let (file_id, caption) = match cached {
+        Some(cached) => cached,
+        None => {
+            let Some(chart) = build_rate_chart(&request).await? else {
+                let no_data = chart_article(
+                    "Недостаточно данных",
+                    "📉 История курсов за этот период ещё не накоплена.",
+                );
+                bot.answer_inline_query(q.id, vec![no_data]).cache_time(10).await?;
+                return Ok(());
+            };
+
+            let uploaded = bot
+                .send_photo(
+                    config.get_storage_chat_id(),
+                    InputFile::memory(chart.png).file_name("chart.png"),
+                )
+                .await?;
+            let file_id = uploaded
+                .photo()
+                .and_then(|sizes| sizes.last())
+                .map(|size| size.file.id.to_string());
+            // the file id stays valid after the message is gone
+            if let Err(e) = bot.delete_message(uploaded.chat.id, uploaded.id).await {
+                warn!("Failed to clean up the uploaded chart: {}", e);
+            }
+            let Some(file_id) = file_id else {
+                return Ok(());
+            };
+
+            redis
+                .set(&cache_key, &(file_id.clone(), chart.caption.clone()), chart_cache_ttl(request.days))
+                .await?;
+            (file_id, chart.caption)
+        }
+    };
+
+    let photo = InlineQueryResultCachedPhoto::new(Uuid::new_v4().to_string(), FileId(file_id))
+        .caption(caption)
+        .parse_mode(ParseMode::Html);
+
+    bot.answer_inline_query(q.id, vec![InlineQueryResult::CachedPhoto(photo)])
+        .cache_time(60)
+        .await?;
+
+    Ok(())
+}
@@ -1,12 +1,21 @@
 use crate::{
     bot::{
+        callbacks::cobalt_pagination::gallery_item_media,
         keyboards::cobalt::{make_photo_pagination_keyboard, make_single_url_keyboard},
         modules::{Owner, cobalt::CobaltSettings},
     },
     core::{
         config::Config,
         db::schemas::settings::Settings,
-        services::cobalt::{DownloadResult, resolve_download_url},
+        services::{
+            cobalt::{
+                DownloadResult, MediaKind, audio_metadata, resolve_audio_url, resolve_download_url,
+                sends_as_audio,
+            },
+            uploader::{
+                audio_cache_key, file_cache_key, upload_audio_to_storage, upload_video_to_storage,
+            },
+        },
     },
     errors::MyError,
 };
@@ -18,7 +27,8 @@ use teloxide::{
     prelude::*,
     types::{
         InlineQuery, InlineQueryResult, InlineQueryResultArticle, InlineQueryResultPhoto,
-        InputFile, InputMedia, InputMediaVideo, InputMessageContent, InputMessageContentText,
+        InputFile, InputMedia, InputMediaAudio, InputMediaDocument, InputMediaVideo, InputMessageContent,
+        InputMessageContentText,
     },
 };
 use url::Url;
@@ -59,9 +69,18 @@ fn build_results_from_media(
                         "Нажмите, чтобы отправить видео",
                     )),
                 )
+                .reply_markup(url_kb.clone());
+
+                let audio_result = InlineQueryResultArticle::new(
+                    format!("cobalt_audio:{}", url_hash),
+                    "🎵 Только аудио",
+                    InputMessageContent::Text(InputMessageContentText::new(
+                        "Нажмите, чтобы отправить аудио",
+                    )),
+                )
                 .reply_markup(url_kb);
 
-                vec![result.into()]
+                vec![result.into(), audio_result.into()]
             } else {
                 vec![
                     InlineQueryResultArticle::new(
@@ -75,33 +94,56 @@ fn build_results_from_media(
                 ]
             }
         }
-        DownloadResult::Photos { urls, .. } => {
-            let total = urls.len();
-            urls.into_iter()
-                .enumerate()
-                .filter_map(|(i, url_str)| {
-                    if let (Ok(photo_url), Ok(thumb_url)) = (url_str.parse(), url_str.parse()) {
-                        let result_id = format!("{}_{}", url_hash, i);
-
-                        let keyboard = if total > 1 {
-                            make_photo_pagination_keyboard(
-                                url_hash,
-                                i,
-                                total,
-                                user_id,
-                                original_url,
-                            )
-                        } else {
-                            make_single_url_keyboard(original_url)
-                        };
+        DownloadResult::Audio { .. } => {
+            let result = InlineQueryResultArticle::new(
+                format!("cobalt_audio:{}", url_hash),
+                "🎵 Скачать аудио",
+                InputMessageContent::Text(InputMessageContentText::new(
+                    "Нажмите, чтобы отправить аудио",
+                )),
+            )
+            .reply_markup(make_single_url_keyboard(original_url));
 
-                        let photo_result =
-                            InlineQueryResultPhoto::new(result_id, photo_url, thumb_url)
-                                .reply_markup(keyboard);
-
-                        Some(photo_result.into())
+            vec![result.into()]
+        }
+        gallery @ (DownloadResult::Photos { .. } | DownloadResult::Gallery { .. }) => {
+            let items = gallery.gallery_items().unwrap_or_default();
+            let total = items.len();
+            items
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, item)| {
+                    let keyboard = if total > 1 {
+                        make_photo_pagination_keyboard(url_hash, i, total, user_id, original_url)
                     } else {
-                        None
+                        make_single_url_keyboard(original_url)
+                    };
+
+                    match item.kind {
+                        MediaKind::Photo => {
+                            let (Ok(photo_url), Ok(thumb_url)) =
+                                (item.url.parse(), item.url.parse())
+                            else {
+                                return None;
+                            };
+                            let result_id = format!("{}_{}", url_hash, i);
+                            let photo_result =
+                                InlineQueryResultPhoto::new(result_id, photo_url, thumb_url)
+                                    .reply_markup(keyboard);
+                            Some(photo_result.into())
+                        }
+                        // the video itself is uploaded once the result is picked
+                        MediaKind::Video => {
+                            let result = InlineQueryResultArticle::new(
+                                format!("cobalt_item:{}:{}", url_hash, i),
+                                format!("Видео {} из {}", i + 1, total),
+                                InputMessageContent::Text(InputMessageContentText::new(
+                                    "Нажмите, чтобы отправить видео",
+                                )),
+                            )
+                            .reply_markup(keyboard);
+                            Some(result.into())
+                        }
                     }
                 })
                 .collect()
@@ -139,8 +181,7 @@ pub async fn handle_cobalt_inline(
     } else {
         let settings = Settings::get_module_settings::<CobaltSettings>(&owner, "cobalt").await?;
 
-        let cobalt_client = config.get_cobalt_client();
-        let result = resolve_download_url(url, &settings, cobalt_client).await;
+        let result = resolve_download_url(url, &settings, config.get_cobalt_pool()).await;
 
         match result {
             Ok(Some(download_result)) => {
@@ -166,15 +207,184 @@ pub async fn handle_cobalt_inline(
     Ok(())
 }
 
+async fn handle_inline_audio(
+    bot: Bot,
+    chosen: ChosenInlineResult,
+    config: Arc<Config>,
+    inline_message_id: String,
+    url_hash: &str,
+) -> Result<(), MyError> {
+    bot.edit_message_text_inline(&inline_message_id, "⏳ Загружаю аудио...")
+        .await?;
+
+    let cache_key = format!("cobalt_cache:{}", url_hash);
+    let original_url = match config.get_redis_client().get::<DownloadResult>(&cache_key).await? {
+        Some(DownloadResult::Video { original_url, .. })
+        | Some(DownloadResult::Audio { original_url, .. }) => original_url,
+        _ => {
+            bot.edit_message_text_inline(inline_message_id, "❌ Ошибка: ссылка не найдена в кэше.")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let owner = Owner {
+        id: chosen.from.id.to_string(),
+        r#type: "user".to_string(),
+    };
+    let settings = Settings::get_module_settings::<CobaltSettings>(&owner, "cobalt").await?;
+
+    let resolved = resolve_audio_url(&original_url, &settings, config.get_cobalt_pool()).await;
+    let Ok(Some(DownloadResult::Audio { url, filename, .. })) = resolved else {
+        bot.edit_message_text_inline(inline_message_id, "❌ Ошибка: не удалось получить аудио.")
+            .await?;
+        return Ok(());
+    };
+
+    let file_key = audio_cache_key(&original_url, &settings.audio_format, &settings.audio_bitrate);
+    let as_audio = sends_as_audio(&filename, &settings.audio_format);
+    let file_id = match upload_audio_to_storage(&config, &url, &filename, &file_key, as_audio).await {
+        Ok(file_id) => file_id,
+        Err(MyError::MediaTooLarge(limit)) => {
+            bot.edit_message_text_inline(
+                inline_message_id,
+                format!("❌ Аудио больше {} МБ, отправить его не получится.", limit),
+            )
+            .await?;
+            return Ok(());
+        }
+        Err(e) => {
+            log::error!("Failed to upload audio for {}: {}", original_url, e);
+            bot.edit_message_text_inline(inline_message_id, "❌ Ошибка: не удалось загрузить аудио.")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let media = if as_audio {
+        let mut audio = InputMediaAudio::new(InputFile::file_id(file_id.into()));
+        let (title, performer) = audio_metadata(&filename);
+        if let Some(title) = title {
+            audio = audio.title(title);
+        }
+        if let Some(performer) = performer {
+            audio = audio.performer(performer);
+        }
+        InputMedia::Audio(audio)
+    } else {
+        InputMedia::Document(InputMediaDocument::new(InputFile::file_id(file_id.into())))
+    };
+
+    if let Err(e) = bot
+        .edit_message_media_inline(&inline_message_id, media)
+        .reply_markup(make_single_url_keyboard(&original_url))
+        .await
+    {
+        log::error!("Failed to edit inline message with audio: {}", e);
+        bot.edit_message_text_inline(inline_message_id, "❌ Ошибка: не удалось отправить аудио.")
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn handle_inline_gallery_item(
+    bot: Bot,
+    chosen: ChosenInlineResult,
+    config: Arc<Config>,
+    inline_message_id: String,
+    url_hash: &str,
+    index: usize,
+) -> Result<(), MyError> {
+    bot.edit_message_text_inline(&inline_message_id, "⏳ Загружаю видео...")
+        .await?;
+
+    let cache_key = format!("cobalt_cache:{}", url_hash);
+    let cached = config
+        .get_redis_client()
+        .get::<DownloadResult>(&cache_key)
+        .await?;
+    let Some((items, original_url)) = cached.and_then(|media| {
+        let items = media.gallery_items()?;
+        Some((items, media.original_url().to_string()))
+    }) else {
+        bot.edit_message_text_inline(inline_message_id, "❌ Ошибка: медиа не найдено в кэше.")
+            .await?;
+        return Ok(());
+    };
+    let Some(item) = items.get(index) else {
+        return Ok(());
+    };
+
+    let media = match gallery_item_media(&config, &original_url, item, index).await {
+        Ok(media) => media,
+        Err(e) => {
+            log::error!(
+                "Failed to prepare carousel item {} of {}: {}",
+                index,
+                original_url,
+                e
+            );
+            bot.edit_message_text_inline(
+                inline_message_id,
+                "❌ Ошибка: не удалось загрузить видео.",
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let keyboard = make_photo_pagination_keyboard(
+        url_hash,
+        index,
+        items.len(),
+        chosen.from.id.0,
+        &original_url,
+    );
+
+    if let Err(e) = bot
+        .edit_message_media_inline(&inline_message_id, media)
+        .reply_markup(keyboard)
+        .await
+    {
+        log::error!("Failed to edit inline message with carousel item: {}", e);
+        bot.edit_message_text_inline(inline_message_id, "❌ Ошибка: не удалось отправить видео.")
+            .await?;
+    }
+
+    Ok(())
+}
+
 pub async fn handle_inline_video(
     bot: Bot,
     chosen: ChosenInlineResult,
     config: Arc<Config>,
 ) -> Result<(), MyError> {
-    let Some(inline_message_id) = chosen.inline_message_id else {
+    let Some(inline_message_id) = chosen.inline_message_id.clone() else {
         return Ok(());
     };
 
+    if let Some(url_hash) = chosen.result_id.strip_prefix("cobalt_audio:") {
+        let url_hash = url_hash.to_string();
+        return handle_inline_audio(bot, chosen, config, inline_message_id, &url_hash).await;
+    }
+
+    if let Some((url_hash, index)) = chosen
+        .result_id
+        .strip_prefix("cobalt_item:")
+        .and_then(|rest| rest.split_once(':'))
+        .and_then(|(hash, index)| Some((hash.to_string(), index.parse::<usize>().ok()?)))
+    {
+        return handle_inline_gallery_item(
+            bot,
+            chosen,
+            config,
+            inline_message_id,
+            &url_hash,
+            index,
+        )
+        .await;
+    }
+
     let Some(url_hash) = chosen.result_id.strip_prefix("cobalt_video:") else {
         return Ok(());
     };
@@ -185,27 +395,52 @@ pub async fn handle_inline_video(
     let redis = config.get_redis_client();
     let cache_key = format!("cobalt_cache:{}", url_hash);
 
-    match redis.get::<DownloadResult>(&cache_key).await? {
-        Some(DownloadResult::Video { url, original_url }) => {
-            let media = InputMedia::Video(InputMediaVideo::new(InputFile::url(url.parse()?)));
-            let url_kb = make_single_url_keyboard(&original_url);
-
-            if let Err(_e) = bot
-                .edit_message_media_inline(&inline_message_id, media)
-                .reply_markup(url_kb)
-                .await
-            {
-                bot.edit_message_text_inline(
-                    inline_message_id,
-                    "❌ Ошибка: не удалось отправить видео.",
-                )
-                .await?;
-            }
+    let Some(DownloadResult::Video { url, original_url }) =
+        redis.get::<DownloadResult>(&cache_key).await?
+    else {
+        bot.edit_message_text_inline(inline_message_id, "❌ Ошибка: видео не найдено в кэше.")
+            .await?;
+        return Ok(());
+    };
+
+    let owner = Owner {
+        id: chosen.from.id.to_string(),
+        r#type: "user".to_string(),
+    };
+    let settings = Settings::get_module_settings::<CobaltSettings>(&owner, "cobalt").await?;
+
+    let file_key = file_cache_key(&original_url, &settings.video_quality);
+    let file_id = match upload_video_to_storage(&config, &url, &file_key).await {
+        Ok(file_id) => file_id,
+        Err(MyError::MediaTooLarge(limit)) => {
+            bot.edit_message_text_inline(
+                inline_message_id,
+                format!("❌ Видео больше {} МБ, отправить его не получится.", limit),
+            )
+            .await?;
+            return Ok(());
         }
-        _ => {
-            bot.edit_message_text_inline(inline_message_id, "❌ Ошибка: видео не найдено в кэше.")
+        Err(e) => {
+            log::error!("Failed to upload {}: {}", original_url, e);
+            bot.edit_message_text_inline(inline_message_id, "❌ Ошибка: не удалось загрузить видео.")
                 .await?;
+            return Ok(());
         }
+    };
+
+    let media = InputMedia::Video(
+        InputMediaVideo::new(InputFile::file_id(file_id.into())).supports_streaming(true),
+    );
+    let url_kb = make_single_url_keyboard(&original_url);
+
+    if let Err(e) = bot
+        .edit_message_media_inline(&inline_message_id, media)
+        .reply_markup(url_kb)
+        .await
+    {
+        log::error!("Failed to edit inline message with video: {}", e);
+        bot.edit_message_text_inline(inline_message_id, "❌ Ошибка: не удалось отправить видео.")
+            .await?;
     }
 
     Ok(())
@@ -3,7 +3,7 @@ use crate::{
     core::{
         config::Config,
         db::schemas::{settings::Settings},
-        services::currency::converter::CURRENCY_REGEX,
+        services::currency::{catalogue::catalogue, converter::build_alias_regex},
     },
     errors::MyError,
 };
@@ -26,12 +26,18 @@ pub async fn is_currency_query(q: InlineQuery) -> bool {
         r#type: "user".to_string(),
     };
 
-    if !CURRENCY_REGEX.is_match(&q.query) {
+    // an alias still needs an amount next to it, so queries without digits never hit the database
+    let known_currency = catalogue().currency_regex.is_match(&q.query);
+    if !known_currency && !q.query.chars().any(|c| c.is_ascii_digit()) {
         return false;
     }
 
     match Settings::get_module_settings::<CurrencySettings>(&owner, "currency").await {
-        Ok(settings) => settings.enabled,
+        Ok(settings) => {
+            settings.enabled
+                && (known_currency
+                    || build_alias_regex(&settings.aliases).is_some_and(|re| re.is_match(&q.query)))
+        }
         Err(e) => {
             error!(
                 "DB error checking currency module status for user {}: {}",
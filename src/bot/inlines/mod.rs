@@ -1,3 +1,4 @@
+pub mod chart;
 pub mod cobalter;
 pub mod currency;
 pub mod whisper;
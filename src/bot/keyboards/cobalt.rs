@@ -28,3 +28,10 @@ pub fn make_photo_pagination_keyboard(
         })
         .build()
 }
+
+pub fn make_audio_only_keyboard(url_hash: &str) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+        "🎵 Только аудио",
+        format!("cobalt_audio:{}", url_hash),
+    )]])
+}
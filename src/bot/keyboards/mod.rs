@@ -1,4 +1,5 @@
 pub mod cobalt;
 pub mod delete;
 pub mod transcription;
+pub mod transcripts;
 pub mod translate;
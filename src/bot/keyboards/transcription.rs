@@ -1,4 +1,7 @@
-use crate::util::paginator::{FrameBuild, Paginator};
+use crate::{
+    core::config::json::SummaryStyle,
+    util::paginator::{FrameBuild, Paginator},
+};
 use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
 
 pub const TRANSCRIPTION_MODULE_KEY: &str = "speech";
@@ -7,22 +10,54 @@ pub fn create_transcription_keyboard(
     current_page: usize,
     total_pages: usize,
     user_id: u64,
+    allow_summaries: bool,
 ) -> InlineKeyboardMarkup {
     let summary_button = InlineKeyboardButton::callback("✨", format!("summarize:{}", user_id));
+    let translate_button =
+        InlineKeyboardButton::callback("🌐", format!("speech_translate:{}", user_id));
+    let srt_button =
+        InlineKeyboardButton::callback("📄 SRT", format!("speech_export:srt:{}", user_id));
+    let vtt_button =
+        InlineKeyboardButton::callback("📄 VTT", format!("speech_export:vtt:{}", user_id));
     let delete_button = InlineKeyboardButton::callback("🗑️", format!("delete_msg:{}", user_id));
 
+    let top_row = if allow_summaries {
+        vec![summary_button, translate_button]
+    } else {
+        vec![translate_button]
+    };
+
     Paginator::new(TRANSCRIPTION_MODULE_KEY, total_pages)
         .current_page(current_page)
-        .add_bottom_row(vec![summary_button])
+        .add_bottom_row(top_row)
+        .add_bottom_row(vec![srt_button, vtt_button])
         .add_bottom_row(vec![delete_button])
         .build()
 }
 
-pub fn create_summary_keyboard() -> InlineKeyboardMarkup {
-    InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
-        "⬅️ Назад",
-        "back_to_full",
-    )]])
+pub fn create_summary_keyboard(user_id: u64) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![
+        vec![InlineKeyboardButton::callback(
+            "✨ Другой формат",
+            format!("summarize:{}", user_id),
+        )],
+        vec![InlineKeyboardButton::callback("⬅️ Назад", "back_to_full")],
+    ])
+}
+
+pub fn create_summary_styles_keyboard(styles: &[SummaryStyle], user_id: u64) -> InlineKeyboardMarkup {
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = styles
+        .iter()
+        .map(|style| {
+            vec![InlineKeyboardButton::callback(
+                style.title.clone(),
+                format!("summarize_style:{}:{}", style.key, user_id),
+            )]
+        })
+        .collect();
+    rows.push(vec![InlineKeyboardButton::callback("⬅️ Назад", "back_to_full")]);
+
+    InlineKeyboardMarkup::new(rows)
 }
 
 pub fn create_retry_keyboard(message_id: i32, user_id: u64, action_type: &str, attempt: u32) -> InlineKeyboardMarkup {
@@ -0,0 +1,65 @@
+use crate::{
+    core::db::schemas::transcript::Transcript,
+    util::paginator::{FrameBuild, ItemsBuild, Paginator},
+};
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+
+pub const TRANSCRIPTS_KEY: &str = "transcripts";
+pub const TRANSCRIPTS_PER_PAGE: usize = 8;
+
+// `transcripts` is only the current page, the database already did the paging
+pub fn create_transcripts_list_keyboard(
+    transcripts: &[Transcript],
+    current_page: usize,
+    total: usize,
+    user_id: u64,
+) -> InlineKeyboardMarkup {
+    Paginator::from(TRANSCRIPTS_KEY, transcripts)
+        .per_page(TRANSCRIPTS_PER_PAGE)
+        .columns(1)
+        .current_page(current_page)
+        .set_total_items(total)
+        .set_items_paged()
+        .set_callback_formatter(move |page| format!("{}:page:{}:{}", TRANSCRIPTS_KEY, page, user_id))
+        .build(|transcript| {
+            let date = chrono::DateTime::from_timestamp(transcript.created_at, 0)
+                .map(|d| d.format("%d.%m.%y %H:%M").to_string())
+                .unwrap_or_default();
+            let preview: String = transcript.text.chars().take(32).collect();
+            InlineKeyboardButton::callback(
+                format!("{} · {}", date, preview.replace('\n', " ")),
+                format!(
+                    "{}:open:{}:{}",
+                    TRANSCRIPTS_KEY,
+                    transcript.hex_id().unwrap_or_default(),
+                    user_id
+                ),
+            )
+        })
+}
+
+pub fn create_transcript_view_keyboard(
+    transcript_id: &str,
+    current_page: usize,
+    total_pages: usize,
+    user_id: u64,
+) -> InlineKeyboardMarkup {
+    let paginator = Paginator::new(TRANSCRIPTS_KEY, total_pages)
+        .current_page(current_page)
+        .set_callback_formatter(|page| {
+            format!("{}:view:{}:{}:{}", TRANSCRIPTS_KEY, transcript_id, page, user_id)
+        })
+        .add_bottom_row(vec![
+            InlineKeyboardButton::callback(
+                "⬅️ К списку",
+                format!("{}:page:0:{}", TRANSCRIPTS_KEY, user_id),
+            ),
+            InlineKeyboardButton::callback(
+                "🗑️ Удалить",
+                format!("{}:del:{}:{}", TRANSCRIPTS_KEY, transcript_id, user_id),
+            ),
+        ]);
+
+    // both build traits are in scope here, so the frame one is called explicitly
+    FrameBuild::build(&paginator)
+}
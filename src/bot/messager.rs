@@ -1,8 +1,12 @@
 use crate::{
     bot::{
-        keyboards::delete::delete_message_button, messages::sounder::sound_handlers, modules::Owner,
+        keyboards::delete::delete_message_button,
+        messages::{downloader::download_handler, sounder::sound_handlers},
+        modules::{Owner, cobalt::CobaltSettings, speech::get_chat_speech_settings},
+    },
+    core::{
+        config::Config, db::schemas::settings::Settings, services::cobalt::find_supported_url,
     },
-    core::config::Config,
     errors::MyError,
 };
 use log::error;
@@ -23,7 +27,13 @@ pub async fn handle_speech(bot: Bot, message: Message) -> Result<(), MyError> {
             return;
         }
 
-        if let Err(e) = sound_handlers(bot, message.clone(), &config).await {
+        // on-demand mode means only /sr, see commands/speech_recognition.rs
+        let settings = get_chat_speech_settings(&message.chat).await;
+        if !settings.enabled || !settings.auto_transcribe {
+            return;
+        }
+
+        if let Err(e) = sound_handlers(bot, message.clone(), &config, settings).await {
             error!("Sound handler failed: {:?}", e);
         }
     });
@@ -31,8 +41,42 @@ pub async fn handle_speech(bot: Bot, message: Message) -> Result<(), MyError> {
     Ok(())
 }
 
+async fn get_chat_cobalt_settings(message: &Message) -> Option<CobaltSettings> {
+    let owner = Owner {
+        id: message.chat.id.to_string(),
+        r#type: (if message.chat.is_private() { "user" } else { "group" }).to_string(),
+    };
+    Settings::get_module_settings::<CobaltSettings>(&owner, "cobalt")
+        .await
+        .ok()
+        .filter(|settings| settings.enabled && settings.auto_download)
+}
+
+// runs next to the currency conversion instead of claiming the update, a link can come with an amount
+fn spawn_media_link(bot: &Bot, message: &Message, config: &Config) {
+    if message.via_bot.is_some() || message.from.as_ref().is_none_or(|user| user.is_bot) {
+        return;
+    }
+    let Some(url) = message.text().and_then(find_supported_url) else {
+        return;
+    };
+
+    let (bot, message, config) = (bot.clone(), message.clone(), config.clone());
+    task::spawn(async move {
+        let Some(settings) = get_chat_cobalt_settings(&message).await else {
+            return;
+        };
+
+        // groups stay quiet on failure, the link itself is still there
+        if let Err(e) = download_handler(bot, message.clone(), &config, &settings, &url).await {
+            error!("Auto download of {} failed: {:?}", url, e);
+        }
+    });
+}
+
 pub async fn handle_currency(bot: Bot, message: Message) -> Result<(), MyError> {
     let config = Config::new().await;
+    spawn_media_link(&bot, &message, &config);
 
     task::spawn(async move {
         let user = message.from.clone().unwrap();
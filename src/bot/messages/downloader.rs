@@ -0,0 +1,367 @@
+use crate::{
+    bot::{keyboards::cobalt::make_audio_only_keyboard, modules::cobalt::CobaltSettings},
+    core::{
+        config::Config,
+        services::{
+            cobalt::{
+                DownloadResult, MediaItem, MediaKind, audio_metadata, resolve_audio_url,
+                resolve_download_url, sends_as_audio,
+            },
+            uploader::{
+                audio_cache_key, file_cache_key, gallery_cache_key, prepare_audio, prepare_video,
+                remember_file_id,
+            },
+        },
+    },
+    errors::MyError,
+};
+use log::{error, warn};
+use teloxide::{
+    prelude::*,
+    types::{
+        InputFile, InputMedia, InputMediaPhoto, InputMediaVideo, MessageId, ParseMode,
+        ReplyParameters,
+    },
+    utils::html,
+};
+
+const MEDIA_GROUP_LIMIT: usize = 10;
+
+fn make_caption(original_url: &str, settings: &CobaltSettings) -> Option<String> {
+    settings
+        .attribution
+        .then(|| format!("🔗 <a href=\"{}\">Источник</a>", html::escape(original_url)))
+}
+
+pub fn url_hash(url: &str) -> String {
+    format!("{:x}", md5::compute(url))
+}
+
+async fn resolve_cached(
+    url: &str,
+    config: &Config,
+    settings: &CobaltSettings,
+) -> Result<Option<DownloadResult>, MyError> {
+    let redis = config.get_redis_client();
+    let cache_key = format!("cobalt_cache:{}", url_hash(url));
+
+    if let Ok(Some(cached)) = redis.get::<DownloadResult>(&cache_key).await {
+        return Ok(Some(cached));
+    }
+
+    let result = resolve_download_url(url, settings, config.get_cobalt_pool()).await?;
+    if let Some(download_result) = &result
+        && let Err(e) = redis.set(&cache_key, download_result, 42 * 60 * 60).await
+    {
+        error!("Failed to cache cobalt result: {}", e);
+    }
+    Ok(result)
+}
+
+async fn reply_too_large(
+    bot: &Bot,
+    chat_id: ChatId,
+    reply_to: MessageId,
+    limit: u64,
+    what: &str,
+) -> Result<(), MyError> {
+    bot.send_message(
+        chat_id,
+        format!("❌ {} больше {} МБ, отправить его не получится.", what, limit),
+    )
+    .reply_parameters(ReplyParameters::new(reply_to))
+    .await?;
+    Ok(())
+}
+
+pub async fn send_audio_reply(
+    bot: &Bot,
+    config: &Config,
+    chat_id: ChatId,
+    reply_to: MessageId,
+    settings: &CobaltSettings,
+    media: DownloadResult,
+) -> Result<(), MyError> {
+    let DownloadResult::Audio {
+        url,
+        original_url,
+        filename,
+    } = media
+    else {
+        return Ok(());
+    };
+
+    let cache_key = audio_cache_key(&original_url, &settings.audio_format, &settings.audio_bitrate);
+    let audio = match prepare_audio(config, &url, &cache_key).await {
+        Ok(audio) => audio,
+        Err(MyError::MediaTooLarge(limit)) => {
+            return reply_too_large(bot, chat_id, reply_to, limit, "Аудио").await;
+        }
+        Err(e) => return Err(e),
+    };
+
+    let mut input_file = audio.input_file();
+    if !filename.is_empty() {
+        input_file = input_file.file_name(filename.clone());
+    }
+
+    let caption = make_caption(&original_url, settings);
+    let sent = if sends_as_audio(&filename, &settings.audio_format) {
+        let mut request = bot
+            .send_audio(chat_id, input_file)
+            .reply_parameters(ReplyParameters::new(reply_to));
+        let (title, performer) = audio_metadata(&filename);
+        if let Some(title) = title {
+            request = request.title(title);
+        }
+        if let Some(performer) = performer {
+            request = request.performer(performer);
+        }
+        if let Some(caption) = caption {
+            request = request.caption(caption).parse_mode(ParseMode::Html);
+        }
+        request.await?
+    } else {
+        let mut request = bot
+            .send_document(chat_id, input_file)
+            .reply_parameters(ReplyParameters::new(reply_to));
+        if let Some(caption) = caption {
+            request = request.caption(caption).parse_mode(ParseMode::Html);
+        }
+        request.await?
+    };
+    remember_file_id(config, &cache_key, &sent).await;
+    Ok(())
+}
+
+// "🎵 Только аудио" under a downloaded video
+pub async fn audio_only_handler(
+    bot: &Bot,
+    config: &Config,
+    chat_id: ChatId,
+    reply_to: MessageId,
+    settings: &CobaltSettings,
+    original_url: &str,
+) -> Result<bool, MyError> {
+    match resolve_audio_url(original_url, settings, config.get_cobalt_pool()).await? {
+        Some(media) => {
+            send_audio_reply(bot, config, chat_id, reply_to, settings, media).await?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+// telegram shows the caption of the first album item as the caption of the whole album
+fn caption_first(group: &mut [InputMedia], caption: &str) {
+    match group.first_mut() {
+        Some(InputMedia::Photo(photo)) => {
+            photo.caption = Some(caption.to_string());
+            photo.parse_mode = Some(ParseMode::Html);
+        }
+        Some(InputMedia::Video(video)) => {
+            video.caption = Some(caption.to_string());
+            video.parse_mode = Some(ParseMode::Html);
+        }
+        _ => {}
+    }
+}
+
+// photos go by url, videos are uploaded, every item keeps its place in the post
+async fn send_gallery(
+    bot: &Bot,
+    config: &Config,
+    message: &Message,
+    settings: &CobaltSettings,
+    items: &[MediaItem],
+    original_url: &str,
+) -> Result<(), MyError> {
+    let caption = make_caption(original_url, settings);
+    let mut videos = Vec::new(); // (index, cache key, source), kept alive until the upload is done
+
+    for (index, item) in items.iter().enumerate() {
+        if item.kind != MediaKind::Video {
+            continue;
+        }
+        let cache_key = gallery_cache_key(original_url, index);
+        match prepare_video(config, &item.url, &cache_key).await {
+            Ok(source) => videos.push((index, cache_key, source)),
+            Err(e) => warn!(
+                "Skipping carousel video {} of {}: {}",
+                index + 1,
+                original_url,
+                e
+            ),
+        }
+    }
+
+    let mut media = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let input_media = match item.kind {
+            MediaKind::Photo => {
+                InputMedia::Photo(InputMediaPhoto::new(InputFile::url(item.url.parse()?)))
+            }
+            MediaKind::Video => {
+                let Some((_, _, source)) = videos.iter().find(|(i, _, _)| *i == index) else {
+                    continue;
+                };
+                InputMedia::Video(
+                    InputMediaVideo::new(source.input_file()).supports_streaming(true),
+                )
+            }
+        };
+        media.push((index, input_media));
+    }
+
+    for (chunk_index, chunk) in media.chunks(MEDIA_GROUP_LIMIT).enumerate() {
+        let mut group: Vec<InputMedia> = chunk.iter().map(|(_, media)| media.clone()).collect();
+        if chunk_index == 0
+            && let Some(caption) = &caption
+        {
+            caption_first(&mut group, caption);
+        }
+
+        // albums need at least two items
+        let sent = match group.pop() {
+            Some(InputMedia::Photo(photo)) if group.is_empty() => {
+                let mut request = bot
+                    .send_photo(message.chat.id, photo.media)
+                    .reply_parameters(ReplyParameters::new(message.id));
+                if let Some(caption) = photo.caption {
+                    request = request.caption(caption).parse_mode(ParseMode::Html);
+                }
+                vec![request.await?]
+            }
+            Some(InputMedia::Video(video)) if group.is_empty() => {
+                let mut request = bot
+                    .send_video(message.chat.id, video.media)
+                    .supports_streaming(true)
+                    .reply_parameters(ReplyParameters::new(message.id));
+                if let Some(caption) = video.caption {
+                    request = request.caption(caption).parse_mode(ParseMode::Html);
+                }
+                vec![request.await?]
+            }
+            Some(last) => {
+                group.push(last);
+                bot.send_media_group(message.chat.id, group)
+                    .reply_parameters(ReplyParameters::new(message.id))
+                    .await?
+            }
+            None => continue,
+        };
+
+        for ((index, _), sent_message) in chunk.iter().zip(&sent) {
+            if let Some((_, cache_key, _)) = videos.iter().find(|(i, _, _)| i == index) {
+                remember_file_id(config, cache_key, sent_message).await;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn download_handler(
+    bot: Bot,
+    message: Message,
+    config: &Config,
+    settings: &CobaltSettings,
+    url: &str,
+) -> Result<(), MyError> {
+    let Some(media) = resolve_cached(url, config, settings).await? else {
+        return Ok(());
+    };
+
+    match media {
+        DownloadResult::Video {
+            url: video_url,
+            original_url,
+        } => {
+            let cache_key = file_cache_key(&original_url, &settings.video_quality);
+            let video = match prepare_video(config, &video_url, &cache_key).await {
+                Ok(video) => video,
+                Err(MyError::MediaTooLarge(limit)) => {
+                    return reply_too_large(&bot, message.chat.id, message.id, limit, "Видео").await;
+                }
+                Err(e) => return Err(e),
+            };
+
+            let mut request = bot
+                .send_video(message.chat.id, video.input_file())
+                .supports_streaming(true)
+                .reply_markup(make_audio_only_keyboard(&url_hash(url)))
+                .reply_parameters(ReplyParameters::new(message.id));
+            if let Some(caption) = make_caption(&original_url, settings) {
+                request = request.caption(caption).parse_mode(ParseMode::Html);
+            }
+            let sent = request.await?;
+            remember_file_id(config, &cache_key, &sent).await;
+        }
+        gallery @ (DownloadResult::Photos { .. } | DownloadResult::Gallery { .. }) => {
+            let items = gallery.gallery_items().unwrap_or_default();
+            send_gallery(
+                &bot,
+                config,
+                &message,
+                settings,
+                &items,
+                gallery.original_url(),
+            )
+            .await?;
+        }
+        audio @ DownloadResult::Audio { .. } => {
+            send_audio_reply(&bot, config, message.chat.id, message.id, settings, audio).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn photo(url: &str) -> InputMedia {
+        InputMedia::Photo(InputMediaPhoto::new(InputFile::url(url.parse().unwrap())))
+    }
+
+    fn caption_of(media: &InputMedia) -> Option<&str> {
+        match media {
+            InputMedia::Photo(photo) => photo.caption.as_deref(),
+            InputMedia::Video(video) => video.caption.as_deref(),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn captions_a_single_item() {
+        let mut group = vec![photo("https://example.com/1.jpg")];
+        caption_first(&mut group, "source");
+
+        assert_eq!(group.len(), 1);
+        assert_eq!(caption_of(&group[0]), Some("source"));
+    }
+
+    #[test]
+    fn captions_only_the_first_item_and_keeps_the_rest() {
+        let mut group = vec![
+            photo("https://example.com/1.jpg"),
+            InputMedia::Video(InputMediaVideo::new(InputFile::file_id("video".into()))),
+            photo("https://example.com/3.jpg"),
+        ];
+        caption_first(&mut group, "source");
+
+        assert_eq!(group.len(), 3);
+        assert_eq!(caption_of(&group[0]), Some("source"));
+        assert!(matches!(group[1], InputMedia::Video(_)));
+        assert_eq!(caption_of(&group[1]), None);
+        assert_eq!(caption_of(&group[2]), None);
+    }
+
+    #[test]
+    fn ignores_an_empty_group() {
+        let mut group: Vec<InputMedia> = Vec::new();
+        caption_first(&mut group, "source");
+        assert!(group.is_empty());
+    }
+}
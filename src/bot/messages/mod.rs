@@ -1,3 +1,4 @@
 pub mod chat;
+pub mod downloader;
 pub mod sound;
 pub mod sounder;
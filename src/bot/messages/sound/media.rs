@@ -0,0 +1,48 @@
+use crate::{
+    bot::{keyboards::delete::delete_message_button, modules::speech::SpeechSettings},
+    core::{
+        config::Config,
+        services::speech_recognition::{get_file_id, transcription_handler},
+    },
+    errors::MyError,
+};
+use log::debug;
+use teloxide::{prelude::*, types::ReplyParameters};
+
+pub async fn media_handler(
+    bot: Bot,
+    msg: Message,
+    config: &Config,
+    settings: &SpeechSettings,
+) -> Result<(), MyError> {
+    if !settings.transcribe_media {
+        return Ok(());
+    }
+
+    // documents that are not audio/video are filtered out here as well
+    let Some(file) = get_file_id(&msg).await else {
+        return Ok(());
+    };
+
+    let max_size = settings.max_media_size_mb * 1024 * 1024;
+    if file.file_size > max_size {
+        debug!(
+            "Skipping media {} in chat {}: {} bytes > {} bytes",
+            file.file_unique_id, msg.chat.id, file.file_size, max_size
+        );
+        let user_id = msg.from.as_ref().map_or(0, |u| u.id.0);
+        bot.send_message(
+            msg.chat.id,
+            format!(
+                "❌ Файл слишком большой для распознавания (лимит {} МБ).",
+                settings.max_media_size_mb
+            ),
+        )
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .reply_markup(delete_message_button(user_id))
+        .await?;
+        return Ok(());
+    }
+
+    transcription_handler(bot, &msg, config).await
+}
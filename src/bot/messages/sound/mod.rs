@@ -1,2 +1,3 @@
+pub mod media;
 pub mod voice;
 pub mod voice_note;
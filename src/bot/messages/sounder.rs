@@ -1,17 +1,30 @@
 use crate::{
-    bot::messages::sound::{voice::voice_handler, voice_note::voice_note_handler},
+    bot::{
+        messages::sound::{media::media_handler, voice::voice_handler, voice_note::voice_note_handler},
+        modules::speech::SpeechSettings,
+    },
     core::config::Config,
     errors::MyError,
 };
 use teloxide::{Bot, prelude::Message};
 
-pub async fn sound_handlers(bot: Bot, message: Message, config: &Config) -> Result<(), MyError> {
+pub async fn sound_handlers(
+    bot: Bot,
+    message: Message,
+    config: &Config,
+    settings: SpeechSettings,
+) -> Result<(), MyError> {
     let config = config.clone();
     tokio::spawn(async move {
         if message.voice().is_some() {
             voice_handler(bot, message, &config).await
         } else if message.video_note().is_some() {
             voice_note_handler(bot, message, &config).await
+        } else if message.audio().is_some()
+            || message.video().is_some()
+            || message.document().is_some()
+        {
+            media_handler(bot, message, &config, &settings).await
         } else {
             Ok(())
         }
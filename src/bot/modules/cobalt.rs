@@ -1,6 +1,9 @@
 use crate::{
     bot::modules::{Module, ModuleSettings, Owner},
-    core::{db::schemas::settings::Settings, services::cobalt::VideoQuality},
+    core::{
+        db::schemas::settings::Settings,
+        services::cobalt::{AudioBitrate, AudioFormat, VideoQuality},
+    },
     errors::MyError,
 };
 use async_trait::async_trait;
@@ -15,6 +18,12 @@ pub struct CobaltSettings {
     pub enabled: bool,
     pub video_quality: VideoQuality,
     pub attribution: bool,
+    #[serde(default)]
+    pub auto_download: bool, // reply with the media to any supported link in the chat
+    #[serde(default)]
+    pub audio_format: AudioFormat,
+    #[serde(default)]
+    pub audio_bitrate: AudioBitrate,
 }
 
 impl Default for CobaltSettings {
@@ -23,6 +32,9 @@ impl Default for CobaltSettings {
             enabled: false,
             video_quality: VideoQuality::Q1080,
             attribution: false,
+            auto_download: false,
+            audio_format: AudioFormat::default(),
+            audio_bitrate: AudioBitrate::default(),
         }
     }
 }
@@ -45,6 +57,8 @@ impl Module for CobaltModule {
         "Можно скачивать видео, фото и аудио с популярных платформ: YouTube, TikTok, Reddit (только видео), Instagram, Bluesky, Bilibili, Dailymotion, Facebook, Loom, OK, Pinterest, Newgrounds, Snapchat, SoundCloud, Streamable, Tumblr, Twitch Clips, Twitter, Iméo, Xiaohongshu. \
         Огромная благодарность создателям утилиты cobalt.tools.\n\n\
         Модуль доступен через inlin'ы: \"@fulturatebot <i>ссылка на медиа</i>\"\n\
+        В чатах можно включить автозагрузку: бот сам ответит медиафайлом на сообщение со ссылкой.\n\
+        Из любого видео можно достать только звук — кнопка «🎵 Только аудио».\n\
         <i>Некоторые сервисы могут быть временно недоступны не по нашей вине из-за ограничений или изменений на стороне платформ.</i>"
     }
 
@@ -91,6 +105,54 @@ impl Module for CobaltModule {
             })
             .collect::<Vec<_>>();
 
+        let format_options = [
+            AudioFormat::Mp3,
+            AudioFormat::Ogg,
+            AudioFormat::Opus,
+            AudioFormat::Best,
+        ];
+        let format_buttons = format_options
+            .iter()
+            .map(|f| {
+                let display_text = if settings.audio_format == *f {
+                    format!("• {} •", f.as_str())
+                } else {
+                    f.as_str().to_string()
+                };
+                let cb_data = format!(
+                    "{}:settings:set:audio_format:{}:{}",
+                    self.key(),
+                    f.as_str(),
+                    commander_id
+                );
+                InlineKeyboardButton::callback(display_text, cb_data)
+            })
+            .collect::<Vec<_>>();
+
+        let bitrate_options = [
+            AudioBitrate::K64,
+            AudioBitrate::K128,
+            AudioBitrate::K256,
+            AudioBitrate::K320,
+        ];
+        let bitrate_buttons = bitrate_options
+            .iter()
+            .map(|b| {
+                let display_text = if settings.audio_bitrate == *b {
+                    format!("• {}k •", b.as_str())
+                } else {
+                    format!("{}k", b.as_str())
+                };
+                let cb_data = format!(
+                    "{}:settings:set:audio_bitrate:{}:{}",
+                    self.key(),
+                    b.as_str(),
+                    commander_id
+                );
+                InlineKeyboardButton::callback(display_text, cb_data)
+            })
+            .collect::<Vec<_>>();
+
         let attr_text = if settings.attribution {
             "Атрибуция: Вкл ✅"
         } else {
@@ -103,11 +165,27 @@ impl Module for CobaltModule {
             commander_id
         );
 
+        let auto_text = if settings.auto_download {
+            "Автозагрузка по ссылкам: Вкл ✅"
+        } else {
+            "Автозагрузка по ссылкам: Выкл ❌"
+        };
+        let auto_cb = format!(
+            "{}:settings:set:auto:{}:{}",
+            self.key(),
+            !settings.auto_download,
+            commander_id
+        );
+
         let keyboard = InlineKeyboardMarkup::new(vec![
             vec![toggle_button],
             vec![InlineKeyboardButton::callback("Качество видео", "noop")],
             quality_buttons,
+            vec![InlineKeyboardButton::callback("Аудио: формат и битрейт", "noop")],
+            format_buttons,
+            bitrate_buttons,
             vec![InlineKeyboardButton::callback(attr_text, attr_cb)],
+            vec![InlineKeyboardButton::callback(auto_text, auto_cb)],
             vec![InlineKeyboardButton::callback(
                 "⬅️ Назад",
                 format!("settings_back:{}:{}:{}", owner.r#type, owner.id, commander_id),
@@ -158,6 +236,15 @@ impl Module for CobaltModule {
             ("attribution", val) => {
                 settings.attribution = val.parse().unwrap_or(false);
             }
+            ("auto", val) => {
+                settings.auto_download = val.parse().unwrap_or(false);
+            }
+            ("audio_format", val) => {
+                settings.audio_format = AudioFormat::parse_format(val);
+            }
+            ("audio_bitrate", val) => {
+                settings.audio_bitrate = AudioBitrate::parse_bitrate(val);
+            }
             _ => {}
         }
 
@@ -173,7 +260,11 @@ impl Module for CobaltModule {
     }
 
     fn designed_for(&self, owner_type: &str) -> bool {
-        owner_type == "user"
+        owner_type == "user" || owner_type == "group"
+    }
+
+    fn is_inline(&self) -> bool {
+        true
     }
 
     async fn is_enabled(&self, owner: &Owner) -> bool {
@@ -189,6 +280,9 @@ impl Module for CobaltModule {
             enabled: true,
             video_quality: VideoQuality::Q1080,
             attribution: false,
+            auto_download: false,
+            audio_format: AudioFormat::default(),
+            audio_bitrate: AudioBitrate::default(),
         };
         Ok(serde_json::to_value(factory_settings)?)
     }
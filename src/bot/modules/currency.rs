@@ -4,7 +4,10 @@ use crate::{
         db::schemas::{group::Group, settings::Settings, user::User},
         services::{
             currencier::handle_currency_update,
-            currency::converter::{get_all_currency_codes, get_default_currencies, CURRENCY_CONFIG_PATH},
+            currency::{
+                converter::{get_all_currency_codes, get_default_currencies, CurrencyAliases},
+                language::{OutputLanguage, OUTPUT_LANGUAGES},
+            },
         },
     },
     errors::MyError,
@@ -15,23 +18,31 @@ use serde::{Deserialize, Serialize};
 use teloxide::{
     prelude::*,
     types::{InlineKeyboardButton, InlineKeyboardMarkup},
+    utils::html,
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CurrencySettings {
     pub enabled: bool,
     pub selected_codes: Vec<String>,
+    #[serde(default)]
+    pub language: OutputLanguage,
+    #[serde(default)]
+    pub aliases: CurrencyAliases,
 }
 
 impl Default for CurrencySettings {
     fn default() -> Self {
         let default_currencies = get_default_currencies()
-            .map(|currencies| currencies.into_iter().map(|c| c.code).collect::<Vec<String>>())
-            .unwrap_or_else(|_| vec!["usd".to_string(), "eur".to_string()]);
+            .into_iter()
+            .map(|c| c.code)
+            .collect::<Vec<String>>();
 
         Self {
             enabled: true,
             selected_codes: default_currencies,
+            language: OutputLanguage::default(),
+            aliases: CurrencyAliases::new(),
         }
     }
 }
@@ -105,6 +116,66 @@ impl Module for CurrencyModule {
             return Ok(());
         }
 
+        if parts.len() >= 2 && parts[0] == "lang" {
+            let Some(language) = OutputLanguage::from_code(parts[1]) else {
+                bot.answer_callback_query(q.id.clone()).await?;
+                return Ok(());
+            };
+            let mut settings: CurrencySettings =
+                Settings::get_module_settings(owner, self.key()).await?;
+            settings.language = language;
+            Settings::update_module_settings(owner, self.key(), settings).await?;
+
+            let (text, keyboard) = self.get_paged_settings_ui(owner, 0, commander_id).await?;
+            bot.edit_message_text(message.chat.id, message.id, text)
+                .reply_markup(keyboard)
+                .parse_mode(teloxide::types::ParseMode::Html)
+                .await?;
+            return Ok(());
+        }
+
+        if parts.len() == 1 && parts[0] == "aliases" {
+            let (text, keyboard) = self.get_aliases_settings_ui(owner, commander_id).await?;
+            bot.edit_message_text(message.chat.id, message.id, text)
+                .reply_markup(keyboard)
+                .parse_mode(teloxide::types::ParseMode::Html)
+                .await?;
+            return Ok(());
+        }
+
+        if parts.len() >= 2 && parts[0] == "alias_del" {
+            if !message.chat.is_private()
+                && !bot
+                    .get_chat_member(message.chat.id, q.from.id)
+                    .await
+                    .is_ok_and(|member| member.is_privileged())
+            {
+                bot.answer_callback_query(q.id.clone())
+                    .text("❌ Удалять названия могут только администраторы.")
+                    .show_alert(true)
+                    .await?;
+                return Ok(());
+            }
+
+            let mut settings: CurrencySettings =
+                Settings::get_module_settings(owner, self.key()).await?;
+            let alias = parts[1]
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| settings.aliases.keys().nth(index).cloned());
+            if let Some(alias) = alias {
+                settings.aliases.remove(&alias);
+                Settings::update_module_settings(owner, self.key(), settings).await?;
+            }
+
+            let (text, keyboard) = self.get_aliases_settings_ui(owner, commander_id).await?;
+            bot.edit_message_text(message.chat.id, message.id, text)
+                .reply_markup(keyboard)
+                .parse_mode(teloxide::types::ParseMode::Html)
+                .await?;
+            return Ok(());
+        }
+
         if parts.len() >= 2 && parts[0] == "toggle" {
             let currency_code = parts[1].to_string();
             let mut settings: CurrencySettings =
@@ -130,6 +201,10 @@ impl Module for CurrencyModule {
         true // all
     }
 
+    fn is_inline(&self) -> bool {
+        true
+    }
+
     async fn is_enabled(&self, owner: &Owner) -> bool {
         if !self.designed_for(&owner.r#type) {
             return false;
@@ -145,6 +220,8 @@ impl Module for CurrencyModule {
                 "UAH".to_string(), "RUB".to_string(), "USD".to_string(),
                 "BYN".to_string(), "EUR".to_string(), "TON".to_string(),
             ],
+            language: OutputLanguage::default(),
+            aliases: CurrencyAliases::new(),
         };
         Ok(serde_json::to_value(factory_settings)?)
     }
@@ -159,10 +236,11 @@ impl CurrencyModule {
     ) -> Result<(String, InlineKeyboardMarkup), MyError> {
         let settings: CurrencySettings = Settings::get_module_settings(owner, self.key()).await?;
         let text = format!(
-            "⚙️ <b>Настройки модуля</b>: {}\n<blockquote>{}</blockquote>\nСтатус: {}\n\nВыберите валюты для отображения.",
+            "⚙️ <b>Настройки модуля</b>: {}\n<blockquote>{}</blockquote>\nСтатус: {}\n\nЯзык ответа: {}\nВыберите валюты для отображения.",
             self.name(),
             self.description(),
-            if settings.enabled { "✅ Включен" } else { "❌ Выключен" }
+            if settings.enabled { "✅ Включен" } else { "❌ Выключен" },
+            settings.language.label()
         );
 
         let toggle_button = InlineKeyboardButton::callback(
@@ -170,7 +248,22 @@ impl CurrencyModule {
             format!("{}:settings:toggle_module:{}", self.key(), commander_id),
         );
 
-        let all_currencies = get_all_currency_codes(CURRENCY_CONFIG_PATH.parse().unwrap())?;
+        let language_buttons = OUTPUT_LANGUAGES
+            .iter()
+            .map(|language| {
+                let display_text = if settings.language == *language {
+                    format!("• {} •", language.label())
+                } else {
+                    language.label().to_string()
+                };
+                InlineKeyboardButton::callback(
+                    display_text,
+                    format!("{}:settings:lang:{}:{}", self.key(), language.code(), commander_id),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let all_currencies = get_all_currency_codes();
 
         let back_button = InlineKeyboardButton::callback(
             "⬅️ Назад",
@@ -198,9 +291,63 @@ impl CurrencyModule {
             });
 
         keyboard.inline_keyboard.insert(0, vec![toggle_button]);
+        keyboard.inline_keyboard.insert(1, language_buttons);
+        keyboard.inline_keyboard.insert(
+            2,
+            vec![InlineKeyboardButton::callback(
+                format!("🏷️ Свои названия ({})", settings.aliases.len()),
+                format!("{}:settings:aliases:{}", self.key(), commander_id),
+            )],
+        );
 
         Ok((text, keyboard))
     }
+
+    async fn get_aliases_settings_ui(
+        &self,
+        owner: &Owner,
+        commander_id: u64,
+    ) -> Result<(String, InlineKeyboardMarkup), MyError> {
+        let settings: CurrencySettings = Settings::get_module_settings(owner, self.key()).await?;
+
+        let list = if settings.aliases.is_empty() {
+            "Пока ничего не добавлено.".to_string()
+        } else {
+            settings
+                .aliases
+                .iter()
+                .map(|(alias, code)| format!("• {} → {}", html::escape(alias), code))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let text = format!(
+            "🏷️ <b>Свои названия валют</b>\n\n\
+            Бот будет понимать эти слова так же, как обычные названия валют.\n\n{}\n\n\
+            Добавить: <code>/alias зелень USD</code>\n\
+            Удалить: нажмите на название ниже или <code>/alias del зелень</code>",
+            list
+        );
+
+        let mut keyboard: Vec<Vec<InlineKeyboardButton>> = settings
+            .aliases
+            .iter()
+            .enumerate()
+            .map(|(index, (alias, code))| {
+                vec![InlineKeyboardButton::callback(
+                    format!("❌ {} → {}", alias, code),
+                    format!("{}:settings:alias_del:{}:{}", self.key(), index, commander_id),
+                )]
+            })
+            .collect();
+
+        keyboard.push(vec![InlineKeyboardButton::callback(
+            "⬅️ Назад",
+            format!("{}:settings:page:0:{}", self.key(), commander_id),
+        )]);
+
+        Ok((text, InlineKeyboardMarkup::new(keyboard)))
+    }
 }
 
 pub async fn currency_codes_handler(bot: Bot, msg: Message, code: String) -> Result<(), MyError> {
@@ -1,6 +1,7 @@
 pub mod cobalt;
 pub mod currency;
 pub mod registry;
+pub mod speech;
 pub mod whisper;
 pub mod translate;
 
@@ -48,6 +49,11 @@ pub trait Module: Send + Sync {
     // this function returns true if the module is designed for the owner type. like if module is designed for user, it will return true for user and false for group, and doesn't show in group
     fn designed_for(&self, owner_type: &str) -> bool;
 
+    // modules that answer inline queries, only they count for the "all inline modules are disabled" answer
+    fn is_inline(&self) -> bool {
+        false
+    }
+
     async fn is_enabled(&self, owner: &Owner) -> bool;
 
     fn factory_settings(&self) -> Result<serde_json::Value, MyError>;
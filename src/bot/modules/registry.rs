@@ -4,6 +4,7 @@ use once_cell::sync::Lazy;
 use std::{collections::BTreeMap, sync::Arc};
 use crate::bot::modules::translate::TranslateModule;
 use crate::bot::modules::whisper::WhisperModule;
+use crate::bot::modules::speech::SpeechModule;
 
 pub struct ModuleManager {
     modules: BTreeMap<String, Arc<dyn Module>>,
@@ -11,7 +12,7 @@ pub struct ModuleManager {
 
 impl ModuleManager {
     fn new() -> Self {
-        let modules: Vec<Arc<dyn Module>> = vec![Arc::new(CobaltModule), Arc::new(CurrencyModule), Arc::new(WhisperModule), Arc::new(TranslateModule)];
+        let modules: Vec<Arc<dyn Module>> = vec![Arc::new(CobaltModule), Arc::new(CurrencyModule), Arc::new(WhisperModule), Arc::new(TranslateModule), Arc::new(SpeechModule)];
 
         let modules = modules
             .into_iter()
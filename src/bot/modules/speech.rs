@@ -0,0 +1,333 @@
+use crate::{
+    bot::modules::{Module, ModuleSettings, Owner},
+    core::db::schemas::settings::Settings,
+    errors::MyError,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use teloxide::{
+    prelude::*,
+    types::{Chat, InlineKeyboardButton, InlineKeyboardMarkup},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SpeechSettings {
+    pub enabled: bool,
+    #[serde(default = "default_true")]
+    pub auto_transcribe: bool,
+    #[serde(default)]
+    pub max_duration: u32, // seconds, 0 = no limit
+    #[serde(default = "default_true")]
+    pub allow_summaries: bool,
+    #[serde(default)]
+    pub diarization: bool,
+    #[serde(default)]
+    pub transcribe_media: bool,
+    #[serde(default = "default_max_media_size_mb")]
+    pub max_media_size_mb: u32,
+    #[serde(default)]
+    pub save_history: bool, // private chats only, stored per user
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_media_size_mb() -> u32 {
+    20 // bot api can't download anything bigger anyway
+}
+
+impl Default for SpeechSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auto_transcribe: true,
+            max_duration: 0,
+            allow_summaries: true,
+            diarization: false,
+            transcribe_media: false,
+            max_media_size_mb: default_max_media_size_mb(),
+            save_history: false,
+        }
+    }
+}
+
+pub async fn get_chat_speech_settings(chat: &Chat) -> SpeechSettings {
+    let owner = Owner {
+        id: chat.id.to_string(),
+        r#type: (if chat.is_private() { "user" } else { "group" }).to_string(),
+    };
+    Settings::get_module_settings(&owner, "speech")
+        .await
+        .unwrap_or_default()
+}
+
+pub async fn get_user_speech_settings(user_id: u64) -> SpeechSettings {
+    let owner = Owner {
+        id: user_id.to_string(),
+        r#type: "user".to_string(),
+    };
+    Settings::get_module_settings(&owner, "speech")
+        .await
+        .unwrap_or_default()
+}
+
+fn format_duration_limit(seconds: u32) -> String {
+    if seconds == 0 {
+        "∞".to_string()
+    } else {
+        format!("{} мин", seconds / 60)
+    }
+}
+
+impl ModuleSettings for SpeechSettings {}
+
+pub struct SpeechModule;
+
+#[async_trait]
+impl Module for SpeechModule {
+    fn key(&self) -> &'static str {
+        "speech"
+    }
+
+    fn name(&self) -> &'static str {
+        "Распознавание речи"
+    }
+
+    fn description(&self) -> &'static str {
+        "Превращает голосовые и видеосообщения в текст: автоматически или только по команде /sr. \
+        Дополнительно можно включить распознавание аудиофайлов, видео и аудио-документов (подкасты, записи экрана и т.д.) с ограничением по размеру файла.\n\n\
+        Любое аудио можно распознать вручную, ответив на него командой /sr. \
+        Также можно ограничить длительность аудио, отключить краткое содержание (✨) \
+        и включить разделение текста по спикерам для записей встреч.\n\n\
+        В личных сообщениях можно включить историю расшифровок: они будут сохраняться, \
+        а найти их можно командой /transcripts."
+    }
+
+    async fn get_settings_ui(
+        &self,
+        owner: &Owner,
+        commander_id: u64,
+    ) -> Result<(String, InlineKeyboardMarkup), MyError> {
+        let settings: SpeechSettings = Settings::get_module_settings(owner, self.key()).await?;
+
+        let text = format!(
+            "⚙️ <b>Настройки модуля</b>: {}\n<blockquote>{}</blockquote>\nСтатус: {}",
+            self.name(),
+            self.description(),
+            if settings.enabled { "✅ Включен" } else { "❌ Выключен" }
+        );
+
+        let toggle_button = InlineKeyboardButton::callback(
+            if settings.enabled { "Выключить модуль" } else { "Включить модуль" },
+            format!("{}:settings:toggle_module:{}", self.key(), commander_id),
+        );
+
+        let auto_text = if settings.auto_transcribe {
+            "Режим: Автоматически 🤖"
+        } else {
+            "Режим: Только по /sr ✋"
+        };
+        let auto_cb = format!(
+            "{}:settings:set:auto:{}:{}",
+            self.key(),
+            !settings.auto_transcribe,
+            commander_id
+        );
+
+        let summaries_text = if settings.allow_summaries {
+            "Краткое содержание: Вкл ✅"
+        } else {
+            "Краткое содержание: Выкл ❌"
+        };
+        let summaries_cb = format!(
+            "{}:settings:set:summaries:{}:{}",
+            self.key(),
+            !settings.allow_summaries,
+            commander_id
+        );
+
+        let diarization_text = if settings.diarization {
+            "Разделение по спикерам: Вкл ✅"
+        } else {
+            "Разделение по спикерам: Выкл ❌"
+        };
+        let diarization_cb = format!(
+            "{}:settings:set:diarization:{}:{}",
+            self.key(),
+            !settings.diarization,
+            commander_id
+        );
+
+        let duration_buttons = [0u32, 60, 300, 900]
+            .iter()
+            .map(|duration| {
+                let display_text = if settings.max_duration == *duration {
+                    format!("• {} •", format_duration_limit(*duration))
+                } else {
+                    format_duration_limit(*duration)
+                };
+                let cb_data = format!(
+                    "{}:settings:set:duration:{}:{}",
+                    self.key(),
+                    duration,
+                    commander_id
+                );
+                InlineKeyboardButton::callback(display_text, cb_data)
+            })
+            .collect::<Vec<_>>();
+
+        let media_text = if settings.transcribe_media {
+            "Аудиофайлы и видео: Вкл ✅"
+        } else {
+            "Аудиофайлы и видео: Выкл ❌"
+        };
+        let media_cb = format!(
+            "{}:settings:set:media:{}:{}",
+            self.key(),
+            !settings.transcribe_media,
+            commander_id
+        );
+
+        let size_buttons = [5u32, 10, 20]
+            .iter()
+            .map(|size| {
+                let display_text = if settings.max_media_size_mb == *size {
+                    format!("• {} МБ •", size)
+                } else {
+                    format!("{} МБ", size)
+                };
+                let cb_data = format!(
+                    "{}:settings:set:size:{}:{}",
+                    self.key(),
+                    size,
+                    commander_id
+                );
+                InlineKeyboardButton::callback(display_text, cb_data)
+            })
+            .collect::<Vec<_>>();
+
+        let history_text = if settings.save_history {
+            "История расшифровок: Вкл ✅"
+        } else {
+            "История расшифровок: Выкл ❌"
+        };
+        let history_cb = format!(
+            "{}:settings:set:history:{}:{}",
+            self.key(),
+            !settings.save_history,
+            commander_id
+        );
+
+        let mut keyboard = vec![
+            vec![toggle_button],
+            vec![InlineKeyboardButton::callback(auto_text, auto_cb)],
+            vec![InlineKeyboardButton::callback(summaries_text, summaries_cb)],
+            vec![InlineKeyboardButton::callback(diarization_text, diarization_cb)],
+            vec![InlineKeyboardButton::callback("Макс. длительность", "noop")],
+            duration_buttons,
+            vec![InlineKeyboardButton::callback(media_text, media_cb)],
+            vec![InlineKeyboardButton::callback("Макс. размер файла", "noop")],
+            size_buttons,
+        ];
+
+        if owner.r#type == "user" {
+            keyboard.push(vec![InlineKeyboardButton::callback(history_text, history_cb)]);
+        }
+
+        keyboard.push(vec![InlineKeyboardButton::callback(
+            "⬅️ Назад",
+            format!("settings_back:{}:{}:{}", owner.r#type, owner.id, commander_id),
+        )]);
+
+        let keyboard = InlineKeyboardMarkup::new(keyboard);
+
+        Ok((text, keyboard))
+    }
+
+    async fn handle_callback(
+        &self,
+        bot: Bot,
+        q: &CallbackQuery,
+        owner: &Owner,
+        data: &str,
+        commander_id: u64,
+    ) -> Result<(), MyError> {
+        let Some(message) = &q.message else { return Ok(()); };
+        let Some(message) = message.regular_message() else { return Ok(()); };
+
+        let parts: Vec<_> = data.split(':').collect();
+
+        if parts.len() == 1 && parts[0] == "toggle_module" {
+            let mut settings: SpeechSettings =
+                Settings::get_module_settings(owner, self.key()).await?;
+            settings.enabled = !settings.enabled;
+            Settings::update_module_settings(owner, self.key(), settings).await?;
+
+            let (text, keyboard) = self.get_settings_ui(owner, commander_id).await?;
+            bot.edit_message_text(message.chat.id, message.id, text)
+                .reply_markup(keyboard)
+                .parse_mode(teloxide::types::ParseMode::Html)
+                .await?;
+            return Ok(());
+        }
+
+        if parts.len() < 3 || parts[0] != "set" {
+            bot.answer_callback_query(q.id.clone()).await?;
+            return Ok(());
+        }
+
+        let mut settings: SpeechSettings = Settings::get_module_settings(owner, self.key()).await?;
+
+        match (parts[1], parts[2]) {
+            ("auto", val) => {
+                settings.auto_transcribe = val.parse().unwrap_or(true);
+            }
+            ("summaries", val) => {
+                settings.allow_summaries = val.parse().unwrap_or(true);
+            }
+            ("diarization", val) => {
+                settings.diarization = val.parse().unwrap_or(false);
+            }
+            ("duration", val) => {
+                settings.max_duration = val.parse().unwrap_or(0);
+            }
+            ("media", val) => {
+                settings.transcribe_media = val.parse().unwrap_or(false);
+            }
+            ("size", val) => {
+                settings.max_media_size_mb = val.parse().unwrap_or(default_max_media_size_mb());
+            }
+            ("history", val) if owner.r#type == "user" => {
+                settings.save_history = val.parse().unwrap_or(false);
+            }
+            _ => {}
+        }
+
+        Settings::update_module_settings(owner, self.key(), settings).await?;
+
+        let (text, keyboard) = self.get_settings_ui(owner, commander_id).await?;
+        bot.edit_message_text(message.chat.id, message.id, text)
+            .reply_markup(keyboard)
+            .parse_mode(teloxide::types::ParseMode::Html)
+            .await?;
+
+        Ok(())
+    }
+
+    fn designed_for(&self, _owner_type: &str) -> bool {
+        true // all
+    }
+
+    async fn is_enabled(&self, owner: &Owner) -> bool {
+        if !self.designed_for(&owner.r#type) {
+            return false;
+        }
+        let settings: SpeechSettings = Settings::get_module_settings(owner, self.key()).await.unwrap(); // god of unwraps
+        settings.enabled
+    }
+
+    fn factory_settings(&self) -> Result<serde_json::Value, MyError> {
+        Ok(serde_json::to_value(SpeechSettings::default())?)
+    }
+}
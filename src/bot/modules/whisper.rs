@@ -101,6 +101,10 @@ impl Module for WhisperModule {
         owner_type == "user"
     }
 
+    fn is_inline(&self) -> bool {
+        true
+    }
+
     async fn is_enabled(&self, owner: &Owner) -> bool {
         if !self.designed_for(&owner.r#type) {
             return false;
@@ -1,14 +1,353 @@
 use serde::Deserialize;
 use std::{fs::File, io::Read, path::Path};
 
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptionProviderKind {
+    #[default]
+    Gemini,
+    OpenAi,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TranscriptionConfig {
+    #[serde(default)]
+    pub provider: TranscriptionProviderKind,
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub summary_model: Option<String>,
+    #[serde(default)]
+    pub diarization_model: Option<String>,
+    #[serde(default = "default_chunk_seconds")]
+    pub chunk_seconds: u32,
+    #[serde(default = "default_chunk_overlap_seconds")]
+    pub chunk_overlap_seconds: u32,
+    #[serde(default = "default_max_parallel_chunks")]
+    pub max_parallel_chunks: usize,
+    #[serde(default)]
+    pub preprocess: PreprocessConfig,
+}
+
+fn default_chunk_seconds() -> u32 {
+    600
+}
+
+fn default_chunk_overlap_seconds() -> u32 {
+    5
+}
+
+fn default_max_parallel_chunks() -> usize {
+    3
+}
+
+impl Default for TranscriptionConfig {
+    fn default() -> Self {
+        Self {
+            provider: TranscriptionProviderKind::default(),
+            base_url: None,
+            model: None,
+            summary_model: None,
+            diarization_model: None,
+            chunk_seconds: default_chunk_seconds(),
+            chunk_overlap_seconds: default_chunk_overlap_seconds(),
+            max_parallel_chunks: default_max_parallel_chunks(),
+            preprocess: PreprocessConfig::default(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PreprocessConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_true")]
+    pub trim_silence: bool,
+    #[serde(default = "default_silence_threshold_db")]
+    pub silence_threshold_db: i32,
+    #[serde(default = "default_min_silence_seconds")]
+    pub min_silence_seconds: f64,
+    #[serde(default = "default_true")]
+    pub normalize_loudness: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_silence_threshold_db() -> i32 {
+    -40
+}
+
+fn default_min_silence_seconds() -> f64 {
+    1.0
+}
+
+impl Default for PreprocessConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            trim_silence: true,
+            silence_threshold_db: default_silence_threshold_db(),
+            min_silence_seconds: default_min_silence_seconds(),
+            normalize_loudness: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RateProviderKind {
+    Coinbase,
+    Nbu,
+    Ecb,
+    TonApi,
+    CoinGecko,
+    Stooq,
+    GoldApi,
+    Static,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RatesConfig {
+    #[serde(default = "default_fiat_providers")]
+    pub fiat: Vec<RateProviderKind>,
+    #[serde(default = "default_token_providers")]
+    pub tokens: Vec<RateProviderKind>,
+    #[serde(default = "default_market_providers")]
+    pub markets: Vec<RateProviderKind>,
+    #[serde(default)]
+    pub static_file: Option<String>,
+    #[serde(default = "default_max_rate_age_hours")]
+    pub max_age_hours: u64,
+}
+
+fn default_fiat_providers() -> Vec<RateProviderKind> {
+    vec![RateProviderKind::Coinbase, RateProviderKind::Nbu, RateProviderKind::Ecb]
+}
+
+fn default_token_providers() -> Vec<RateProviderKind> {
+    vec![RateProviderKind::TonApi, RateProviderKind::CoinGecko]
+}
+
+fn default_market_providers() -> Vec<RateProviderKind> {
+    vec![RateProviderKind::Stooq, RateProviderKind::GoldApi]
+}
+
+fn default_max_rate_age_hours() -> u64 {
+    72 // nbu and ecb don't publish on weekends
+}
+
+impl Default for RatesConfig {
+    fn default() -> Self {
+        Self {
+            fiat: default_fiat_providers(),
+            tokens: default_token_providers(),
+            markets: default_market_providers(),
+            static_file: None,
+            max_age_hours: default_max_rate_age_hours(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AlertsConfig {
+    #[serde(default = "default_check_interval_secs")]
+    pub check_interval_secs: u64,
+    #[serde(default = "default_utc_offset_hours")]
+    pub utc_offset_hours: i32, // digest times are entered in this timezone
+    #[serde(default = "default_max_alerts_per_chat")]
+    pub max_per_chat: usize,
+}
+
+fn default_check_interval_secs() -> u64 {
+    60
+}
+
+fn default_utc_offset_hours() -> i32 {
+    3
+}
+
+fn default_max_alerts_per_chat() -> usize {
+    20
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_secs: default_check_interval_secs(),
+            utc_offset_hours: default_utc_offset_hours(),
+            max_per_chat: default_max_alerts_per_chat(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct DownloadsConfig {
+    #[serde(default = "default_max_download_mb")]
+    pub max_size_mb: u64,
+    #[serde(default = "default_download_timeout_secs")]
+    pub timeout_secs: u64,
+    #[serde(default = "default_true")]
+    pub transcode: bool, // re-encode anything telegram won't play inline into h264/aac mp4
+    #[serde(default = "default_file_id_ttl_days")]
+    pub file_id_ttl_days: u64,
+}
+
+fn default_max_download_mb() -> u64 {
+    50 // bot api upload limit
+}
+
+fn default_download_timeout_secs() -> u64 {
+    300
+}
+
+fn default_file_id_ttl_days() -> u64 {
+    30
+}
+
+impl Default for DownloadsConfig {
+    fn default() -> Self {
+        Self {
+            max_size_mb: default_max_download_mb(),
+            timeout_secs: default_download_timeout_secs(),
+            transcode: true,
+            file_id_ttl_days: default_file_id_ttl_days(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CobaltInstanceConfig {
+    pub url: String,
+    #[serde(default)]
+    pub api_key_env: Option<String>, // name of the env var holding the key, keys don't go into config.json
+    #[serde(default = "default_instance_weight")]
+    pub weight: u32, // share of requests, 0 keeps the instance as a fallback only
+}
+
+fn default_instance_weight() -> u32 {
+    1
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CobaltConfig {
+    #[serde(default = "default_cobalt_instances")]
+    pub instances: Vec<CobaltInstanceConfig>,
+    #[serde(default = "default_cobalt_user_agent")]
+    pub user_agent: String,
+    #[serde(default = "default_health_check_interval_secs")]
+    pub health_check_interval_secs: u64,
+    #[serde(default = "default_cobalt_cooldown_secs")]
+    pub cooldown_secs: u64, // how long an instance is skipped after a rate limit or failure
+}
+
+fn default_cobalt_instances() -> Vec<CobaltInstanceConfig> {
+    vec![CobaltInstanceConfig {
+        url: "https://cobalt-backend.canine.tools/".to_string(),
+        api_key_env: Some("COBALT_API_KEY".to_string()),
+        weight: default_instance_weight(),
+    }]
+}
+
+fn default_cobalt_user_agent() -> String {
+    "Fulturate/6.6.6 (rust) (+https://github.com/weever1337/fulturate-rs)".to_string()
+}
+
+fn default_health_check_interval_secs() -> u64 {
+    120
+}
+
+fn default_cobalt_cooldown_secs() -> u64 {
+    60
+}
+
+impl Default for CobaltConfig {
+    fn default() -> Self {
+        Self {
+            instances: default_cobalt_instances(),
+            user_agent: default_cobalt_user_agent(),
+            health_check_interval_secs: default_health_check_interval_secs(),
+            cooldown_secs: default_cobalt_cooldown_secs(),
+        }
+    }
+}
+
+// configs written before segments existed don't have this prompt
+fn default_segments_prompt() -> String {
+    "You are an audio transcription service that produces time-stamped segments. Transcribe the spoken words from the audio track verbatim, keeping the original language, and ignore the visual stream completely. Split the speech into short segments of one or two sentences each. Return ONLY a JSON array, without markdown or code fences, where each element looks like {\"start\": 0.0, \"end\": 3.2, \"text\": \"...\"} with start and end given in seconds from the beginning of the file. If no speech is present, return [].".to_string()
+}
+
+fn default_diarization_prompt() -> String {
+    "You are an audio transcription service that separates speakers. Transcribe the spoken words from the audio track verbatim, keeping the original language, and ignore the visual stream completely. Detect who is speaking and label every speaker consistently as \"Speaker 1\", \"Speaker 2\" and so on, in the order they first speak. Start a new segment every time the speaker changes. Return ONLY a JSON array, without markdown or code fences, where each element looks like {\"speaker\": \"Speaker 1\", \"start\": 0.0, \"end\": 3.2, \"text\": \"...\"} with start and end given in seconds from the beginning of the file. If no speech is present, return [].".to_string()
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SummaryStyle {
+    pub key: String,
+    pub title: String,
+    pub prompt: String,
+}
+
+fn default_summary_styles() -> Vec<SummaryStyle> {
+    vec![
+        SummaryStyle {
+            key: "tldr".to_string(),
+            title: "⚡ Кратко".to_string(),
+            prompt: "You are an assistant that transcribes and then summarizes spoken content. First, accurately and fully transcribe the voice message, keeping the original language. Then, briefly summarize the transcribed text in the same language. Output only the final summary. Do not include the full transcription, and do not add any extra words like 'Summary' or 'Transcription'. Do not explain or comment. The output must be plain and concise.".to_string(),
+        },
+        SummaryStyle {
+            key: "bullets".to_string(),
+            title: "📋 Тезисы".to_string(),
+            prompt: "You are an assistant that transcribes and then summarizes spoken content. First, accurately and fully transcribe the voice message, keeping the original language. Then summarize it as a short bulleted list of the key points in the same language, one point per line, each line starting with \"• \". Output only the list. Do not include the full transcription, and do not add any headers, explanations or comments.".to_string(),
+        },
+        SummaryStyle {
+            key: "actions".to_string(),
+            title: "✅ Задачи и даты".to_string(),
+            prompt: "You are an assistant that transcribes spoken content and extracts what needs to be done. First, accurately and fully transcribe the voice message, keeping the original language. Then extract every action item, decision, deadline, date and time mentioned, in the same language, one per line, each line starting with \"• \" and mentioning who is responsible if it is said. If there is nothing like that, answer with one short sentence saying so in the same language. Output only the list. Do not include the full transcription, and do not add any headers, explanations or comments.".to_string(),
+        },
+    ]
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct JsonConfig {
     pub ai_model: String,
     pub ai_prompt: String,
-    pub summarize_prompt: String,
+    #[serde(default)]
+    pub summary_styles: Vec<SummaryStyle>,
+    #[serde(default)]
+    summarize_prompt: Option<String>, // configs from before styles, becomes the prompt of the first style
+    #[serde(default = "default_segments_prompt")]
+    pub segments_prompt: String,
+    #[serde(default = "default_diarization_prompt")]
+    pub diarization_prompt: String,
+    #[serde(default)]
+    pub transcription: TranscriptionConfig,
+    #[serde(default)]
+    pub rates: RatesConfig,
+    #[serde(default)]
+    pub alerts: AlertsConfig,
+    #[serde(default)]
+    pub downloads: DownloadsConfig,
+    #[serde(default)]
+    pub cobalt: CobaltConfig,
 }
 
 impl JsonConfig {
+    fn fill_summary_styles(&mut self) {
+        if !self.summary_styles.is_empty() {
+            return;
+        }
+        self.summary_styles = default_summary_styles();
+        if let Some(prompt) = self.summarize_prompt.take()
+            && let Some(first) = self.summary_styles.first_mut()
+        {
+            first.prompt = prompt;
+        }
+    }
+
     pub fn get_ai_model(&self) -> &str {
         &self.ai_model
     }
@@ -17,8 +356,40 @@ impl JsonConfig {
         &self.ai_prompt
     }
 
-    pub fn get_summarize_prompt(&self) -> &str {
-        &self.summarize_prompt
+    pub fn get_summary_styles(&self) -> &[SummaryStyle] {
+        &self.summary_styles
+    }
+
+    pub fn get_summary_style(&self, key: &str) -> Option<&SummaryStyle> {
+        self.summary_styles.iter().find(|style| style.key == key)
+    }
+
+    pub fn get_segments_prompt(&self) -> &str {
+        &self.segments_prompt
+    }
+
+    pub fn get_diarization_prompt(&self) -> &str {
+        &self.diarization_prompt
+    }
+
+    pub fn get_transcription_config(&self) -> &TranscriptionConfig {
+        &self.transcription
+    }
+
+    pub fn get_rates_config(&self) -> &RatesConfig {
+        &self.rates
+    }
+
+    pub fn get_alerts_config(&self) -> &AlertsConfig {
+        &self.alerts
+    }
+
+    pub fn get_downloads_config(&self) -> &DownloadsConfig {
+        &self.downloads
+    }
+
+    pub fn get_cobalt_config(&self) -> &CobaltConfig {
+        &self.cobalt
     }
 }
 
@@ -26,6 +397,54 @@ pub fn read_json_config<P: AsRef<Path>>(path: P) -> Result<JsonConfig, Box<dyn s
     let mut file = File::open(path)?;
     let mut contents = String::new();
     file.read_to_string(&mut contents)?;
-    let config: JsonConfig = serde_json::from_str(&contents)?;
+    Ok(parse_json_config(&contents)?)
+}
+
+pub fn parse_json_config(contents: &str) -> Result<JsonConfig, serde_json::Error> {
+    let mut config: JsonConfig = serde_json::from_str(contents)?;
+    config.fill_summary_styles();
     Ok(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_config_from_before_summary_styles() {
+        let config = parse_json_config(
+            r#"{"ai_model": "gemini-2.5-flash", "ai_prompt": "transcribe", "summarize_prompt": "summarize"}"#,
+        )
+        .unwrap();
+
+        let styles = config.get_summary_styles();
+        assert_eq!(styles.len(), default_summary_styles().len());
+        assert_eq!(styles[0].prompt, "summarize");
+        assert!(!config.get_segments_prompt().is_empty());
+        assert!(!config.get_diarization_prompt().is_empty());
+    }
+
+    #[test]
+    fn keeps_configured_summary_styles() {
+        let config = parse_json_config(
+            r#"{
+                "ai_model": "gemini-2.5-flash",
+                "ai_prompt": "transcribe",
+                "summarize_prompt": "ignored",
+                "summary_styles": [{"key": "short", "title": "Short", "prompt": "be short"}]
+            }"#,
+        )
+        .unwrap();
+
+        let styles = config.get_summary_styles();
+        assert_eq!(styles.len(), 1);
+        assert_eq!(styles[0].key, "short");
+        assert_eq!(styles[0].prompt, "be short");
+    }
+
+    #[test]
+    fn parses_the_shipped_config() {
+        let config = parse_json_config(include_str!("../../../config.json")).unwrap();
+        assert!(config.get_summary_style("tldr").is_some());
+    }
+}
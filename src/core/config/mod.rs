@@ -1,9 +1,13 @@
-mod json;
+pub mod json;
 
 use crate::core::{
     config::json::{JsonConfig, read_json_config},
     db::redis::RedisCache,
-    services::currency::converter::{CurrencyConverter, OutputLanguage},
+    services::{
+        cobalt_pool::CobaltPool,
+        currency::converter::CurrencyConverter,
+        transcription::{TranscriptionProvider, build_provider},
+    },
 };
 use dotenv::dotenv;
 use log::error;
@@ -14,10 +18,11 @@ use teloxide::prelude::*;
 #[derive(Clone)]
 pub struct Config {
     bot: Bot,
-    cobalt_client: ccobalt::Client,
+    cobalt_pool: CobaltPool,
     #[allow(dead_code)]
     owners: Vec<String>,
     log_chat_id: String,
+    storage_chat_id: ChatId,
     error_chat_thread_id: String,
     #[allow(dead_code)]
     warn_chat_thread_id: String,
@@ -26,6 +31,7 @@ pub struct Config {
     currency_converter: Arc<CurrencyConverter>,
     mongodb_url: String,
     redis_client: RedisCache,
+    transcription_provider: Arc<dyn TranscriptionProvider>,
 }
 
 impl Config {
@@ -36,27 +42,21 @@ impl Config {
             error!("Expected BOT_TOKEN env var");
             std::process::exit(1);
         };
-        let Ok(cobalt_api_key) = std::env::var("COBALT_API_KEY") else {
-            error!("COBALT_API_KEY expected");
-            std::process::exit(1);
-        };
         let Ok(version) = std::env::var("CARGO_PKG_VERSION") else {
             error!("CARGO_PKG_VERSION expected");
             std::process::exit(1);
         };
-        let bot = Bot::new(bot_token);
-
-        let cobalt_client = ccobalt::Client::builder()
-            .base_url("https://cobalt-backend.canine.tools/")
-            .api_key(cobalt_api_key)
-            .user_agent(
-                "Fulturate/6.6.6 (rust) (+https://github.com/weever1337/fulturate-rs)".to_string(),
-            )
-            .build()
-            .unwrap_or_else(|_err| {
-                error!("Failed to build cobalt client");
+        let mut bot = Bot::new(bot_token);
+        // a local bot api server lifts the 20MB download limit for voice and audio files
+        if let Ok(api_url) = std::env::var("TELEGRAM_API_URL")
+            && !api_url.is_empty()
+        {
+            let Ok(api_url) = api_url.parse() else {
+                error!("TELEGRAM_API_URL is not a valid url");
                 std::process::exit(1);
-            });
+            };
+            bot = bot.set_api_url(api_url);
+        }
 
         let owners: Vec<String> = std::env::var("OWNERS")
             .unwrap_or_else(|_| {
@@ -71,6 +71,15 @@ impl Config {
             error!("LOG_CHAT_ID expected");
             std::process::exit(1);
         };
+        // inline results only take media that already lives on telegram, fresh uploads go here first
+        let Some(storage_chat_id) = std::env::var("STORAGE_CHAT_ID")
+            .ok()
+            .and_then(|id| id.trim().parse().ok())
+            .map(ChatId)
+        else {
+            error!("STORAGE_CHAT_ID expected");
+            std::process::exit(1);
+        };
         let error_chat_thread_id: String = std::env::var("ERROR_CHAT_THREAD_ID")
             .ok()
             .and_then(|s| s.parse().ok())
@@ -86,7 +95,11 @@ impl Config {
             error!("Unable to read config.json");
             std::process::exit(1);
         };
-        let currency_converter = Arc::new(CurrencyConverter::new(OutputLanguage::Russian).unwrap()); // TODO: get language from config
+        let cobalt_pool = CobaltPool::new(json_config.get_cobalt_config()).unwrap_or_else(|err| {
+            error!("{}", err);
+            std::process::exit(1);
+        });
+        let currency_converter = Arc::new(CurrencyConverter::new(json_config.get_rates_config()));
         let Ok(mongodb_url) = std::env::var("MONGODB_URL") else {
             error!("MONGODB_URL expected");
             std::process::exit(1);
@@ -103,11 +116,15 @@ impl Config {
         };
         let redis_client = RedisCache::new(redis_client);
 
+        let transcription_provider =
+            build_provider(&json_config, std::env::var("TRANSCRIPTION_API_KEY").ok());
+
         Config {
             bot,
-            cobalt_client,
+            cobalt_pool,
             owners,
             log_chat_id,
+            storage_chat_id,
             error_chat_thread_id,
             warn_chat_thread_id,
             version,
@@ -115,6 +132,7 @@ impl Config {
             currency_converter,
             mongodb_url,
             redis_client,
+            transcription_provider,
         }
     }
 
@@ -122,15 +140,14 @@ impl Config {
         &self.bot
     }
 
-    pub fn get_cobalt_client(&self) -> &ccobalt::Client {
-        &self.cobalt_client
+    pub fn get_cobalt_pool(&self) -> &CobaltPool {
+        &self.cobalt_pool
     }
 
     pub fn get_version(&self) -> &str {
         &self.version
     }
 
-    #[allow(dead_code)]
     pub fn is_id_in_owners(&self, id: String) -> bool {
         self.owners.contains(&id)
     }
@@ -139,6 +156,10 @@ impl Config {
         &self.log_chat_id
     }
 
+    pub fn get_storage_chat_id(&self) -> ChatId {
+        self.storage_chat_id
+    }
+
     pub fn get_error_chat_thread_id(&self) -> &str {
         &self.error_chat_thread_id
     }
@@ -163,4 +184,8 @@ impl Config {
     pub fn get_redis_client(&self) -> &RedisCache {
         &self.redis_client
     }
+
+    pub fn get_transcription_provider(&self) -> &Arc<dyn TranscriptionProvider> {
+        &self.transcription_provider
+    }
 }
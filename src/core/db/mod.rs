@@ -1,3 +1,4 @@
 pub mod functions;
+pub mod mongo;
 pub mod redis;
 pub mod schemas;
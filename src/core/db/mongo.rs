@@ -0,0 +1,16 @@
+use mongodb::{Client, Collection, error::Error};
+use tokio::sync::OnceCell;
+
+const DATABASE: &str = "fulturate";
+
+static CLIENT: OnceCell<Client> = OnceCell::const_new();
+
+// oximod only covers plain crud, sorted, paged and aggregated queries go through the driver
+pub async fn collection<T: Send + Sync>(name: &str) -> Result<Collection<T>, Error> {
+    let client = CLIENT
+        .get_or_try_init(|| async {
+            Client::with_uri_str(std::env::var("MONGODB_URL").unwrap_or_default()).await
+        })
+        .await?;
+    Ok(client.database(DATABASE).collection(name))
+}
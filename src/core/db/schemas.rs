@@ -1,5 +1,8 @@
+pub mod alert;
 pub mod group;
+pub mod rate_snapshot;
 pub mod settings;
+pub mod transcript;
 pub mod user;
 
 use crate::core::services::currency::converter::CurrencyStruct;
@@ -0,0 +1,137 @@
+use mongodb::bson::{doc, oid::ObjectId};
+use oximod::{_error::oximod_error::OxiModError, Model};
+use serde::{Deserialize, Serialize};
+
+pub const KIND_THRESHOLD: &str = "threshold";
+pub const KIND_DIGEST: &str = "digest";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Model)]
+#[db("fulturate")]
+#[collection("alerts")]
+pub struct Alert {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    _id: Option<ObjectId>,
+
+    #[index(name = "chat_id")]
+    pub chat_id: String,
+    pub owner_type: String, // "user" | "group"
+    pub created_by: String,
+    pub kind: String, // KIND_THRESHOLD | KIND_DIGEST
+
+    // threshold: fires once when `from_code/to_code` crosses `value`, re-arms when it goes back
+    #[serde(default)]
+    pub from_code: String,
+    #[serde(default)]
+    pub to_code: String,
+    #[serde(default)]
+    pub above: bool,
+    #[serde(default)]
+    pub value: f64,
+    #[serde(default)]
+    pub armed: bool,
+
+    // digest: minutes since local midnight, `last_sent` is the local YYYY-MM-DD
+    #[serde(default)]
+    pub digest_minute: u32,
+    #[serde(default)]
+    pub last_sent: String,
+
+    pub created_at: i64,
+}
+
+impl Alert {
+    pub fn hex_id(&self) -> Option<String> {
+        self._id.map(|id| id.to_hex())
+    }
+
+    pub fn is_digest(&self) -> bool {
+        self.kind == KIND_DIGEST
+    }
+
+    pub async fn add_threshold(
+        chat_id: &str,
+        owner_type: &str,
+        created_by: &str,
+        from_code: &str,
+        to_code: &str,
+        above: bool,
+        value: f64,
+    ) -> Result<(), OxiModError> {
+        Self::new()
+            .chat_id(chat_id.to_string())
+            .owner_type(owner_type.to_string())
+            .created_by(created_by.to_string())
+            .kind(KIND_THRESHOLD.to_string())
+            .from_code(from_code.to_string())
+            .to_code(to_code.to_string())
+            .above(above)
+            .value(value)
+            .armed(true)
+            .created_at(chrono::Utc::now().timestamp())
+            .save()
+            .await?;
+        Ok(())
+    }
+
+    // one digest per chat, setting a new time replaces the old one
+    pub async fn set_digest(
+        chat_id: &str,
+        owner_type: &str,
+        created_by: &str,
+        digest_minute: u32,
+    ) -> Result<(), OxiModError> {
+        let filter = doc! { "chat_id": chat_id, "kind": KIND_DIGEST };
+
+        if Self::find_one(filter.clone()).await?.is_some() {
+            Self::update_one(
+                filter,
+                doc! { "$set": { "digest_minute": digest_minute, "created_by": created_by, "last_sent": "" } },
+            )
+            .await?;
+        } else {
+            Self::new()
+                .chat_id(chat_id.to_string())
+                .owner_type(owner_type.to_string())
+                .created_by(created_by.to_string())
+                .kind(KIND_DIGEST.to_string())
+                .digest_minute(digest_minute)
+                .created_at(chrono::Utc::now().timestamp())
+                .save()
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn for_chat(chat_id: &str) -> Result<Vec<Self>, OxiModError> {
+        let mut alerts = Self::find(doc! { "chat_id": chat_id }).await?;
+        alerts.sort_by_key(|alert| alert.created_at);
+        Ok(alerts)
+    }
+
+    pub async fn all() -> Result<Vec<Self>, OxiModError> {
+        Self::find(doc! {}).await
+    }
+
+    pub async fn remove(chat_id: &str, id: &str) -> Result<bool, OxiModError> {
+        let Ok(object_id) = ObjectId::parse_str(id) else {
+            return Ok(false);
+        };
+        let deleted = Self::delete_one(doc! { "_id": object_id, "chat_id": chat_id }).await?;
+        Ok(deleted.deleted_count > 0)
+    }
+
+    pub async fn set_armed(&self, armed: bool) -> Result<(), OxiModError> {
+        if let Some(id) = self._id {
+            Self::update_one(doc! { "_id": id }, doc! { "$set": { "armed": armed } }).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn mark_sent(&self, date: &str) -> Result<(), OxiModError> {
+        if let Some(id) = self._id {
+            Self::update_one(doc! { "_id": id }, doc! { "$set": { "last_sent": date } }).await?;
+        }
+        Ok(())
+    }
+}
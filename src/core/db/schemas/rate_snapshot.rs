@@ -0,0 +1,120 @@
+use crate::core::db::mongo;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{Document, doc, from_document, oid::ObjectId},
+    error::Error,
+};
+use oximod::{_error::oximod_error::OxiModError, Model};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const SNAPSHOT_BUCKET_SECS: i64 = 60 * 10;
+const COLLECTION: &str = "rate_snapshots";
+
+#[derive(Debug, Deserialize)]
+pub struct PairPoint {
+    pub fetched_at: i64,
+    pub from: f64,
+    pub to: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Model)]
+#[db("fulturate")]
+#[collection("rate_snapshots")]
+pub struct RateSnapshot {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    _id: Option<ObjectId>,
+
+    #[index(unique, name = "bucket")]
+    pub bucket: i64,
+    #[index(name = "date")]
+    pub date: String, // YYYY-MM-DD, utc
+    pub fetched_at: i64,
+    pub base_code: String,
+    pub rates: HashMap<String, f64>,
+}
+
+impl RateSnapshot {
+    // every Config builds its own converter, so fetches are deduplicated into 10 minute buckets
+    pub async fn record(
+        fetched_at: DateTime<chrono::Utc>,
+        base_code: &str,
+        rates: HashMap<String, f64>,
+    ) -> Result<(), OxiModError> {
+        let timestamp = fetched_at.timestamp();
+        let bucket = timestamp / SNAPSHOT_BUCKET_SECS;
+
+        if Self::find_one(doc! { "bucket": bucket }).await?.is_some() {
+            return Ok(());
+        }
+
+        Self::new()
+            .bucket(bucket)
+            .date(fetched_at.date_naive().format("%Y-%m-%d").to_string())
+            .fetched_at(timestamp)
+            .base_code(base_code.to_string())
+            .rates(rates)
+            .save()
+            .await?;
+
+        Ok(())
+    }
+
+    // one point per bucket for a single pair, only the two rates ever leave the database.
+    // codes come from the validated catalogue, so they are safe to use as field paths
+    pub async fn pair_since(
+        date: NaiveDate,
+        from_code: &str,
+        to_code: &str,
+        max_points: usize,
+    ) -> Result<Vec<PairPoint>, Error> {
+        let since = date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc().timestamp();
+        let period = (Utc::now().timestamp() - since).max(SNAPSHOT_BUCKET_SECS);
+        let bucket_secs = (period / max_points.max(1) as i64).max(SNAPSHOT_BUCKET_SECS);
+
+        let from_field = format!("rates.{}", from_code);
+        let to_field = format!("rates.{}", to_code);
+        let pipeline = vec![
+            doc! { "$match": {
+                "date": { "$gte": date.format("%Y-%m-%d").to_string() },
+                &from_field: { "$exists": true },
+                &to_field: { "$exists": true },
+            } },
+            doc! { "$project": {
+                "_id": 0,
+                "fetched_at": 1,
+                "from": format!("${}", from_field),
+                "to": format!("${}", to_field),
+            } },
+            doc! { "$sort": { "fetched_at": 1 } },
+            doc! { "$group": {
+                "_id": { "$subtract": ["$fetched_at", { "$mod": ["$fetched_at", bucket_secs] }] },
+                "fetched_at": { "$last": "$fetched_at" },
+                "from": { "$last": "$from" },
+                "to": { "$last": "$to" },
+            } },
+            doc! { "$sort": { "fetched_at": 1 } },
+        ];
+
+        let documents: Vec<Document> = mongo::collection::<Self>(COLLECTION)
+            .await?
+            .aggregate(pipeline)
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(documents
+            .into_iter()
+            .filter_map(|document| from_document(document).ok())
+            .collect())
+    }
+
+    pub async fn latest_on(date: NaiveDate) -> Result<Option<Self>, Error> {
+        mongo::collection::<Self>(COLLECTION)
+            .await?
+            .find_one(doc! { "date": date.format("%Y-%m-%d").to_string() })
+            .sort(doc! { "fetched_at": -1 })
+            .await
+    }
+}
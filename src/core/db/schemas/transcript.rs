@@ -0,0 +1,156 @@
+use crate::core::db::mongo;
+use futures::TryStreamExt;
+use mongodb::{
+    Collection, IndexModel,
+    bson::{Document, doc, oid::ObjectId},
+    error::Error,
+    options::IndexOptions,
+};
+use oximod::{_error::oximod_error::OxiModError, Model};
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+
+const COLLECTION: &str = "transcripts";
+// list buttons only show the beginning of the text, the rest stays in the database
+const PREVIEW_CHARS: i32 = 64;
+
+static TEXT_INDEX: OnceCell<()> = OnceCell::const_new();
+
+#[derive(Debug, Clone, Serialize, Deserialize, Model)]
+#[db("fulturate")]
+#[collection("transcripts")]
+pub struct Transcript {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    _id: Option<ObjectId>,
+
+    #[index(name = "user_id")]
+    pub user_id: String,
+    pub chat_id: String,
+    pub file_unique_id: String,
+    pub text: String,
+
+    // enough to summarize or re-transcribe once redis has forgotten the file
+    #[serde(default)]
+    pub file_id: String,
+    #[serde(default)]
+    pub mime_type: String,
+    // "chat_id:message_id" of every bot reply that shows this transcript
+    #[serde(default)]
+    #[index(name = "messages")]
+    pub messages: Vec<String>,
+
+    #[serde(default)]
+    pub diarized: bool,
+    pub created_at: i64, // unix seconds
+}
+
+impl Transcript {
+    pub fn hex_id(&self) -> Option<String> {
+        self._id.map(|id| id.to_hex())
+    }
+
+    pub fn message_ref(chat_id: &str, message_id: i32) -> String {
+        format!("{}:{}", chat_id, message_id)
+    }
+
+    // one record per file, re-transcribing just refreshes the text and remembers the new reply
+    pub async fn remember(&self, message_ref: &str) -> Result<(), Error> {
+        Self::collection()
+            .await?
+            .update_one(
+                doc! { "user_id": &self.user_id, "file_unique_id": &self.file_unique_id },
+                doc! {
+                    "$set": {
+                        "text": &self.text,
+                        "diarized": self.diarized,
+                        "file_id": &self.file_id,
+                        "mime_type": &self.mime_type,
+                        "created_at": self.created_at,
+                    },
+                    "$setOnInsert": { "chat_id": &self.chat_id },
+                    "$addToSet": { "messages": message_ref },
+                },
+            )
+            .upsert(true)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn find_by_message(message_ref: &str) -> Result<Option<Self>, OxiModError> {
+        Self::find_one(doc! { "messages": message_ref }).await
+    }
+
+    // user_id leads the text index, so a search only ever scans one user's transcripts
+    async fn collection() -> Result<Collection<Self>, Error> {
+        let collection = mongo::collection::<Self>(COLLECTION).await?;
+        TEXT_INDEX
+            .get_or_try_init(|| async {
+                let index = IndexModel::builder()
+                    .keys(doc! { "user_id": 1, "text": "text" })
+                    .options(
+                        IndexOptions::builder()
+                            .name("user_text".to_string())
+                            .default_language("russian".to_string())
+                            .build(),
+                    )
+                    .build();
+                collection.create_index(index).await.map(|_| ())
+            })
+            .await?;
+        Ok(collection)
+    }
+
+    fn search_filter(user_id: &str, query: &str) -> Document {
+        let mut filter = doc! { "user_id": user_id };
+        if !query.trim().is_empty() {
+            filter.insert("$text", doc! { "$search": query.trim() });
+        }
+        filter
+    }
+
+    pub async fn count_matching(user_id: &str, query: &str) -> Result<u64, Error> {
+        Self::collection()
+            .await?
+            .count_documents(Self::search_filter(user_id, query))
+            .await
+    }
+
+    // best matches first when searching, newest first otherwise. the text is cut to a preview
+    pub async fn search(
+        user_id: &str,
+        query: &str,
+        skip: usize,
+        limit: usize,
+    ) -> Result<Vec<Self>, Error> {
+        let sort = if query.trim().is_empty() {
+            doc! { "created_at": -1 }
+        } else {
+            doc! { "score": { "$meta": "textScore" }, "created_at": -1 }
+        };
+
+        Self::collection()
+            .await?
+            .find(Self::search_filter(user_id, query))
+            .sort(sort)
+            .skip(skip as u64)
+            .limit(limit as i64)
+            .projection(doc! {
+                "user_id": 1,
+                "chat_id": 1,
+                "file_unique_id": 1,
+                "diarized": 1,
+                "created_at": 1,
+                "text": { "$substrCP": ["$text", 0, PREVIEW_CHARS] },
+            })
+            .await?
+            .try_collect()
+            .await
+    }
+
+    pub async fn find_for_user(user_id: &str, id: &str) -> Result<Option<Self>, OxiModError> {
+        let Ok(object_id) = ObjectId::parse_str(id) else {
+            return Ok(None);
+        };
+        Self::find_one(doc! { "_id": object_id, "user_id": user_id }).await
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{
+    bot::modules::{Owner, currency::CurrencySettings},
+    core::{
+        config::Config,
+        db::schemas::{alert::Alert, settings::Settings},
+        services::currency::{
+            converter::{CurrencyAliases, CurrencyConverter},
+            history::format_rate,
+        },
+    },
+    errors::MyError,
+};
+use chrono::{FixedOffset, Timelike, Utc};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::{sync::Arc, time::Duration};
+use teloxide::{
+    prelude::*,
+    types::{ChatId, ParseMode},
+};
+use tokio::time::{Instant, MissedTickBehavior, interval_at};
+
+// a digest that was missed (restart, api outage) is still sent within this window
+const DIGEST_WINDOW_MINUTES: u32 = 60;
+
+static TIME_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\d{1,2})[:.](\d{2})$").unwrap());
+
+pub const ALERT_USAGE: &str = "Использование:\n\
+    <code>/alert USD UAH &gt; 42</code> — уведомить, когда курс станет выше 42\n\
+    <code>/alert EUR &lt; 45</code> — когда станет ниже (вторая валюта по умолчанию UAH)\n\
+    <code>/alert digest 09:00</code> — ежедневная сводка по выбранным валютам\n\
+    <code>/alert digest off</code> — отключить сводку\n\
+    <code>/alert list</code> — список уведомлений\n\
+    <code>/alert del 2</code> — удалить уведомление по номеру";
+
+pub enum AlertCommand {
+    List,
+    Delete(usize),
+    Digest(Option<u32>),
+    Threshold {
+        from: String,
+        to: String,
+        above: bool,
+        value: f64,
+    },
+}
+
+fn parse_time(token: &str) -> Option<u32> {
+    let cap = TIME_RE.captures(token)?;
+    let hour: u32 = cap[1].parse().ok()?;
+    let minute: u32 = cap[2].parse().ok()?;
+    (hour < 24 && minute < 60).then_some(hour * 60 + minute)
+}
+
+// "usd uah > 42", "usd/uah>42", "€ < 45,5"
+pub fn parse_alert_command(
+    converter: &CurrencyConverter,
+    aliases: &CurrencyAliases,
+    args: &str,
+) -> Option<AlertCommand> {
+    let args = args.trim();
+    let mut tokens = args.split_whitespace();
+
+    match tokens.next().map(|t| t.to_lowercase()).as_deref() {
+        None | Some("list" | "список") => return Some(AlertCommand::List),
+        Some("del" | "delete" | "удалить") => {
+            let index = tokens.next()?.parse::<usize>().ok()?;
+            return (index > 0).then_some(AlertCommand::Delete(index));
+        }
+        Some("digest" | "дайджест" | "сводка") => {
+            let token = tokens.next()?;
+            if matches!(token.to_lowercase().as_str(), "off" | "выкл") {
+                return Some(AlertCommand::Digest(None));
+            }
+            return parse_time(token).map(|minute| AlertCommand::Digest(Some(minute)));
+        }
+        _ => {}
+    }
+
+    let above = args.contains('>');
+    if !above && !args.contains('<') {
+        return None;
+    }
+
+    let normalized = args.replace(['>', '<', '/'], " ");
+    let mut tokens: Vec<&str> = normalized.split_whitespace().collect();
+    let value: f64 = tokens.pop()?.replace(',', ".").parse().ok()?;
+    if !value.is_finite() || value <= 0.0 {
+        return None;
+    }
+
+    let mut currencies = tokens
+        .into_iter()
+        .map(|token| converter.find_currency_info_by_identifier(token, aliases))
+        .collect::<Option<Vec<_>>>()?
+        .into_iter();
+
+    let from = currencies.next()?.code.clone();
+    let to = match currencies.next() {
+        Some(to) => to.code.clone(),
+        None => (if from == "UAH" { "USD" } else { "UAH" }).to_string(),
+    };
+    if from == to || currencies.next().is_some() {
+        return None;
+    }
+
+    Some(AlertCommand::Threshold {
+        from,
+        to,
+        above,
+        value,
+    })
+}
+
+pub fn describe_alert(alert: &Alert) -> String {
+    if alert.is_digest() {
+        format!(
+            "📊 Сводка курсов каждый день в {:02}:{:02}",
+            alert.digest_minute / 60,
+            alert.digest_minute % 60
+        )
+    } else {
+        format!(
+            "🔔 {}/{} {} {}",
+            alert.from_code,
+            alert.to_code,
+            if alert.above { "&gt;" } else { "&lt;" },
+            format_rate(alert.value)
+        )
+    }
+}
+
+pub fn local_offset(config: &Config) -> FixedOffset {
+    let hours = config.get_json_config().get_alerts_config().utc_offset_hours;
+    FixedOffset::east_opt(hours * 3600).unwrap_or_else(|| FixedOffset::east_opt(0).unwrap())
+}
+
+async fn chat_currency_settings(alert: &Alert) -> Result<CurrencySettings, MyError> {
+    let owner = Owner {
+        id: alert.chat_id.clone(),
+        r#type: alert.owner_type.clone(),
+    };
+    Settings::get_module_settings(&owner, "currency").await
+}
+
+async fn check_threshold(config: &Config, alert: &Alert, chat_id: ChatId) -> Result<(), MyError> {
+    // alerts stay saved while the module is off, they just keep quiet
+    if !chat_currency_settings(alert).await?.enabled {
+        return Ok(());
+    }
+
+    let rate = config
+        .get_currency_converter()
+        .get_pair_rate(&alert.from_code, &alert.to_code)
+        .await?;
+
+    let crossed = if alert.above { rate > alert.value } else { rate < alert.value };
+
+    if crossed && alert.armed {
+        let text = format!(
+            "🔔 <b>{}/{}</b> {} {}\nСейчас: <b>{}</b>",
+            alert.from_code,
+            alert.to_code,
+            if alert.above { "выше" } else { "ниже" },
+            format_rate(alert.value),
+            format_rate(rate)
+        );
+        config
+            .get_bot()
+            .send_message(chat_id, text)
+            .parse_mode(ParseMode::Html)
+            .await?;
+        alert.set_armed(false).await?;
+    } else if !crossed && !alert.armed {
+        alert.set_armed(true).await?;
+    }
+
+    Ok(())
+}
+
+async fn send_digest(
+    config: &Config,
+    alert: &Alert,
+    chat_id: ChatId,
+    today: &str,
+    now_minute: u32,
+) -> Result<(), MyError> {
+    let due = now_minute >= alert.digest_minute
+        && now_minute < alert.digest_minute + DIGEST_WINDOW_MINUTES;
+    if !due || alert.last_sent == today {
+        return Ok(());
+    }
+
+    let settings = chat_currency_settings(alert).await?;
+
+    if settings.enabled && settings.selected_codes.len() > 1 {
+        let digest = config
+            .get_currency_converter()
+            .format_digest(&settings.selected_codes, settings.language)
+            .await?;
+        let text = format!("📊 <b>Курсы валют</b>\n\n{}", digest);
+        config
+            .get_bot()
+            .send_message(chat_id, text)
+            .parse_mode(ParseMode::Html)
+            .await?;
+    }
+
+    alert.mark_sent(today).await?;
+    Ok(())
+}
+
+async fn check_alerts(config: &Config) -> Result<(), MyError> {
+    let alerts = Alert::all().await?;
+    if alerts.is_empty() {
+        return Ok(());
+    }
+
+    let now = Utc::now().with_timezone(&local_offset(config));
+    let today = now.format("%Y-%m-%d").to_string();
+    let now_minute = now.hour() * 60 + now.minute();
+
+    for alert in alerts {
+        let Ok(chat_id) = alert.chat_id.parse::<i64>().map(ChatId) else {
+            continue;
+        };
+
+        let result = if alert.is_digest() {
+            send_digest(config, &alert, chat_id, &today, now_minute).await
+        } else {
+            check_threshold(config, &alert, chat_id).await
+        };
+
+        if let Err(e) = result {
+            warn!(
+                "Alert {:?} for chat {} failed: {}",
+                alert.hex_id(),
+                alert.chat_id,
+                e
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn run_alert_scheduler(config: Arc<Config>) {
+    let period = Duration::from_secs(
+        config
+            .get_json_config()
+            .get_alerts_config()
+            .check_interval_secs
+            .max(10),
+    );
+    info!("Alert scheduler started, checking every {:?}", period);
+
+    // the first tick is delayed so the database connection is up by then
+    let mut interval = interval_at(Instant::now() + period, period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+        if let Err(e) = check_alerts(&config).await {
+            warn!("Alert check failed: {}", e);
+        }
+    }
+}
@@ -0,0 +1,329 @@
+// tiny line chart renderer, a whole plotting crate (plus fonts in docker) is overkill for one line.
+// labels use a built-in 5x7 bitmap font that only knows what numbers and dates need
+type Rgb = [u8; 3];
+
+const BACKGROUND: Rgb = [250, 250, 250];
+const GRID: Rgb = [225, 225, 225];
+const AXIS: Rgb = [150, 150, 150];
+const LABEL: Rgb = [90, 90, 90];
+const UP: Rgb = [46, 160, 67];
+const DOWN: Rgb = [218, 54, 51];
+const MIN_MARKER: Rgb = [218, 54, 51];
+const MAX_MARKER: Rgb = [46, 160, 67];
+
+const MARGIN: f64 = 24.0;
+const GRID_LINES: usize = 4;
+const X_LABELS: usize = 5;
+
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+const GLYPH_SCALE: usize = 2;
+const GLYPH_ADVANCE: usize = (GLYPH_WIDTH + 1) * GLYPH_SCALE;
+const LABEL_HEIGHT: usize = GLYPH_HEIGHT * GLYPH_SCALE;
+
+// one row per byte, the low five bits are the pixels from left to right
+fn glyph(c: char) -> Option<[u8; GLYPH_HEIGHT]> {
+    Some(match c {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        '/' => [0x01, 0x02, 0x02, 0x04, 0x08, 0x08, 0x10],
+        _ => return None,
+    })
+}
+
+fn text_width(text: &str) -> f64 {
+    (text.chars().count() * GLYPH_ADVANCE).saturating_sub(GLYPH_SCALE) as f64
+}
+
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32, color: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: color.repeat((width * height) as usize),
+        }
+    }
+
+    fn blend(&mut self, x: i64, y: i64, color: Rgb, alpha: f64) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return;
+        }
+        let offset = ((y as u32 * self.width + x as u32) * 3) as usize;
+        for (channel, value) in color.iter().enumerate() {
+            let old = self.pixels[offset + channel] as f64;
+            self.pixels[offset + channel] = (old + (*value as f64 - old) * alpha).round() as u8;
+        }
+    }
+
+    fn disc(&mut self, cx: f64, cy: f64, radius: f64, color: Rgb) {
+        let (x0, x1) = ((cx - radius).floor() as i64, (cx + radius).ceil() as i64);
+        let (y0, y1) = ((cy - radius).floor() as i64, (cy + radius).ceil() as i64);
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                let distance = ((x as f64 - cx).powi(2) + (y as f64 - cy).powi(2)).sqrt();
+                // one pixel of soft edge instead of real antialiasing
+                let alpha = (radius + 0.5 - distance).clamp(0.0, 1.0);
+                if alpha > 0.0 {
+                    self.blend(x, y, color, alpha);
+                }
+            }
+        }
+    }
+
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), thickness: f64, color: Rgb) {
+        let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+        let steps = (length * 2.0).ceil().max(1.0) as usize;
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            self.disc(
+                from.0 + (to.0 - from.0) * t,
+                from.1 + (to.1 - from.1) * t,
+                thickness / 2.0,
+                color,
+            );
+        }
+    }
+
+    fn horizontal(&mut self, x0: f64, x1: f64, y: f64, color: Rgb) {
+        for x in x0.round() as i64..=x1.round() as i64 {
+            self.blend(x, y.round() as i64, color, 1.0);
+        }
+    }
+
+    fn vertical(&mut self, x: f64, y0: f64, y1: f64, color: Rgb) {
+        for y in y0.round() as i64..=y1.round() as i64 {
+            self.blend(x.round() as i64, y, color, 1.0);
+        }
+    }
+
+    // (x, y) is the top left corner, characters the font doesn't know are left blank
+    fn text(&mut self, x: f64, y: f64, text: &str, color: Rgb) {
+        let (x, y) = (x.round() as i64, y.round() as i64);
+        for (index, c) in text.chars().enumerate() {
+            let Some(rows) = glyph(c) else {
+                continue;
+            };
+            let left = x + (index * GLYPH_ADVANCE) as i64;
+            for (row, bits) in rows.iter().enumerate() {
+                for column in 0..GLYPH_WIDTH {
+                    if bits & (0x10 >> column) == 0 {
+                        continue;
+                    }
+                    for dy in 0..GLYPH_SCALE {
+                        for dx in 0..GLYPH_SCALE {
+                            self.blend(
+                                left + (column * GLYPH_SCALE + dx) as i64,
+                                y + (row * GLYPH_SCALE + dy) as i64,
+                                color,
+                                1.0,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn encode_png(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)?;
+        Ok(out)
+    }
+}
+
+pub struct ChartPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+// x and y are scaled to fit, the formatters turn axis values into tick labels. returns png bytes
+pub fn render_line_chart(
+    points: &[ChartPoint],
+    width: u32,
+    height: u32,
+    format_x: impl Fn(f64) -> String,
+    format_y: impl Fn(f64) -> String,
+) -> Result<Vec<u8>, png::EncodingError> {
+    let mut canvas = Canvas::new(width, height, BACKGROUND);
+    let (w, h) = (width as f64, height as f64);
+
+    let (min_x, max_x) = points
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.x), hi.max(p.x)));
+    let (min_y, max_y) = points
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.y), hi.max(p.y)));
+    // flat lines go through the middle instead of dividing by zero
+    let flat = max_y - min_y <= f64::EPSILON;
+    let span_x = (max_x - min_x).max(f64::EPSILON);
+    let (bottom_y, span_y) = if flat { (min_y - 0.5, 1.0) } else { (min_y, max_y - min_y) };
+
+    // without a line there is nothing to label, the empty grid is still drawn
+    let y_labels: Vec<String> = (0..=GRID_LINES)
+        .map(|i| match points.len() {
+            0 | 1 => String::new(),
+            _ => format_y(bottom_y + span_y * i as f64 / GRID_LINES as f64),
+        })
+        .collect();
+    let left = MARGIN + y_labels.iter().map(|label| text_width(label)).fold(0.0, f64::max) + 8.0;
+    let right = w - MARGIN;
+    let top = MARGIN;
+    let bottom = h - MARGIN - LABEL_HEIGHT as f64 - 8.0;
+
+    for (i, label) in y_labels.iter().enumerate() {
+        let y = bottom - (bottom - top) * i as f64 / GRID_LINES as f64;
+        canvas.horizontal(left, right, y, GRID);
+        canvas.text(
+            left - 8.0 - text_width(label),
+            y - LABEL_HEIGHT as f64 / 2.0,
+            label,
+            LABEL,
+        );
+    }
+    canvas.vertical(left, top, bottom, AXIS);
+    canvas.horizontal(left, right, bottom, AXIS);
+
+    if points.len() < 2 {
+        return canvas.encode_png();
+    }
+
+    for i in 0..X_LABELS {
+        let t = i as f64 / (X_LABELS - 1) as f64;
+        let x = left + (right - left) * t;
+        let label = format_x(min_x + span_x * t);
+        canvas.vertical(x, bottom, bottom + 4.0, AXIS);
+        // the outer labels are kept inside the image
+        let label_x = (x - text_width(&label) / 2.0).clamp(0.0, w - text_width(&label));
+        canvas.text(label_x, bottom + 8.0, &label, LABEL);
+    }
+
+    let project = |p: &ChartPoint| {
+        (
+            left + (p.x - min_x) / span_x * (right - left),
+            bottom - (p.y - bottom_y) / span_y * (bottom - top),
+        )
+    };
+    let projected: Vec<(f64, f64)> = points.iter().map(project).collect();
+
+    let color = if points[points.len() - 1].y >= points[0].y { UP } else { DOWN };
+
+    // light area under the line
+    for pair in projected.windows(2) {
+        let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+        for x in x0.round() as i64..x1.round() as i64 {
+            let t = if x1 > x0 { (x as f64 - x0) / (x1 - x0) } else { 0.0 };
+            let line_y = (y0 + (y1 - y0) * t).round() as i64;
+            for y in line_y..bottom as i64 {
+                canvas.blend(x, y, color, 0.12);
+            }
+        }
+    }
+
+    for pair in projected.windows(2) {
+        canvas.line(pair[0], pair[1], 3.0, color);
+    }
+
+    let min_index = (0..points.len())
+        .min_by(|a, b| points[*a].y.total_cmp(&points[*b].y))
+        .unwrap_or(0);
+    let max_index = (0..points.len())
+        .max_by(|a, b| points[*a].y.total_cmp(&points[*b].y))
+        .unwrap_or(0);
+
+    for (index, marker) in [(min_index, MIN_MARKER), (max_index, MAX_MARKER)] {
+        let (x, y) = projected[index];
+        canvas.disc(x, y, 7.0, BACKGROUND);
+        canvas.disc(x, y, 5.0, marker);
+    }
+    let (x, y) = projected[projected.len() - 1];
+    canvas.disc(x, y, 5.0, color);
+
+    canvas.encode_png()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(png_bytes: &[u8]) -> (u32, u32, Vec<u8>) {
+        let decoder = png::Decoder::new(std::io::Cursor::new(png_bytes));
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        pixels.truncate(info.buffer_size());
+        (info.width, info.height, pixels)
+    }
+
+    fn has_color(pixels: &[u8], color: Rgb) -> bool {
+        pixels.chunks(3).any(|pixel| pixel == color)
+    }
+
+    fn render(points: &[ChartPoint]) -> Vec<u8> {
+        render_line_chart(points, 400, 200, |x| format!("{:.0}", x), |y| format!("{:.2}", y))
+            .unwrap()
+    }
+
+    #[test]
+    fn round_trips_through_a_png_decoder() {
+        let points = [
+            ChartPoint { x: 0.0, y: 1.0 },
+            ChartPoint { x: 1.0, y: 3.0 },
+            ChartPoint { x: 2.0, y: 2.0 },
+        ];
+        let (width, height, pixels) = decode(&render(&points));
+
+        assert_eq!((width, height), (400, 200));
+        assert_eq!(pixels.len(), 400 * 200 * 3);
+        assert_eq!(&pixels[..3], &BACKGROUND);
+        assert!(has_color(&pixels, UP));
+        assert!(has_color(&pixels, LABEL));
+        assert!(has_color(&pixels, AXIS));
+    }
+
+    #[test]
+    fn falling_rate_is_drawn_in_red() {
+        let points = [ChartPoint { x: 0.0, y: 5.0 }, ChartPoint { x: 1.0, y: 4.0 }];
+        let (_, _, pixels) = decode(&render(&points));
+        assert!(has_color(&pixels, DOWN));
+    }
+
+    #[test]
+    fn renders_flat_and_empty_series() {
+        let flat = [ChartPoint { x: 0.0, y: 2.0 }, ChartPoint { x: 1.0, y: 2.0 }];
+        let (width, height, _) = decode(&render(&flat));
+        assert_eq!((width, height), (400, 200));
+
+        let (_, _, pixels) = decode(&render(&[]));
+        assert!(!has_color(&pixels, LABEL));
+    }
+
+    #[test]
+    fn knows_every_character_of_numeric_labels() {
+        for c in "0123456789.,:-+/".chars() {
+            assert!(glyph(c).is_some(), "no glyph for {:?}", c);
+        }
+        assert_eq!(text_width("12"), (2 * GLYPH_ADVANCE - GLYPH_SCALE) as f64);
+    }
+}
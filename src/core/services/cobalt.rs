@@ -1,9 +1,69 @@
-use crate::{bot::modules::cobalt::CobaltSettings, errors::MyError};
+use crate::{
+    bot::modules::cobalt::CobaltSettings, core::services::cobalt_pool::CobaltPool, errors::MyError,
+};
 use ccobalt::model::{
-    request::{DownloadRequest, FilenameStyle},
+    request::{DownloadMode, DownloadRequest, FilenameStyle},
     response::DownloadResponse,
 };
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use url::Url;
+
+static LINK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"https?://[^\s<>]+").unwrap());
+
+// what cobalt can handle, subdomains (m., vm., music.) match too
+const SUPPORTED_HOSTS: &[&str] = &[
+    "youtube.com",
+    "youtu.be",
+    "tiktok.com",
+    "reddit.com",
+    "redd.it",
+    "instagram.com",
+    "bsky.app",
+    "bilibili.com",
+    "b23.tv",
+    "dailymotion.com",
+    "dai.ly",
+    "facebook.com",
+    "fb.watch",
+    "loom.com",
+    "ok.ru",
+    "pinterest.com",
+    "pin.it",
+    "newgrounds.com",
+    "snapchat.com",
+    "soundcloud.com",
+    "streamable.com",
+    "tumblr.com",
+    "twitch.tv",
+    "twitter.com",
+    "x.com",
+    "vimeo.com",
+    "vk.com",
+    "vkvideo.ru",
+    "xiaohongshu.com",
+    "xhslink.com",
+];
+
+pub fn is_supported_url(url: &str) -> bool {
+    let Some(host) = Url::parse(url).ok().and_then(|url| url.host_str().map(str::to_lowercase))
+    else {
+        return false;
+    };
+    SUPPORTED_HOSTS
+        .iter()
+        .any(|supported| host == *supported || host.ends_with(&format!(".{}", supported)))
+}
+
+// the first link in the text that cobalt knows how to download
+pub fn find_supported_url(text: &str) -> Option<String> {
+    LINK_REGEX
+        .find_iter(text)
+        .map(|m| m.as_str().trim_end_matches(['.', ',', ')', '!', '?']))
+        .find(|url| is_supported_url(url))
+        .map(str::to_string)
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum VideoQuality {
@@ -33,6 +93,76 @@ impl VideoQuality {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub enum AudioFormat {
+    Best,
+    #[default]
+    Mp3,
+    Ogg,
+    Opus,
+}
+
+impl AudioFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AudioFormat::Best => "best",
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::Ogg => "ogg",
+            AudioFormat::Opus => "opus",
+        }
+    }
+
+    pub fn parse_format(s: &str) -> Self {
+        match s {
+            "best" => AudioFormat::Best,
+            "ogg" => AudioFormat::Ogg,
+            "opus" => AudioFormat::Opus,
+            _ => AudioFormat::Mp3,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub enum AudioBitrate {
+    K64,
+    #[default]
+    K128,
+    K256,
+    K320,
+}
+
+impl AudioBitrate {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AudioBitrate::K64 => "64",
+            AudioBitrate::K128 => "128",
+            AudioBitrate::K256 => "256",
+            AudioBitrate::K320 => "320",
+        }
+    }
+
+    pub fn parse_bitrate(s: &str) -> Self {
+        match s {
+            "64" => AudioBitrate::K64,
+            "256" => AudioBitrate::K256,
+            "320" => AudioBitrate::K320,
+            _ => AudioBitrate::K128,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum MediaKind {
+    Photo,
+    Video,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MediaItem {
+    pub kind: MediaKind,
+    pub url: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum DownloadResult {
     Video {
@@ -43,14 +173,81 @@ pub enum DownloadResult {
         urls: Vec<String>,
         original_url: String,
     },
+    Audio {
+        url: String,
+        original_url: String,
+        filename: String,
+    },
+    // carousels mixing photos and videos, in the order of the post
+    Gallery {
+        items: Vec<MediaItem>,
+        original_url: String,
+    },
 }
 
-pub async fn resolve_download_url(
-    url: &str,
-    settings: &CobaltSettings,
-    client: &ccobalt::Client,
-) -> Result<Option<DownloadResult>, MyError> {
-    let cobalt_req = DownloadRequest {
+impl DownloadResult {
+    // photos and carousels are paginated item by item, photos are just a gallery without videos
+    pub fn gallery_items(&self) -> Option<Vec<MediaItem>> {
+        match self {
+            DownloadResult::Photos { urls, .. } => Some(
+                urls.iter()
+                    .map(|url| MediaItem {
+                        kind: MediaKind::Photo,
+                        url: url.clone(),
+                    })
+                    .collect(),
+            ),
+            DownloadResult::Gallery { items, .. } => Some(items.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn original_url(&self) -> &str {
+        match self {
+            DownloadResult::Video { original_url, .. }
+            | DownloadResult::Photos { original_url, .. }
+            | DownloadResult::Audio { original_url, .. }
+            | DownloadResult::Gallery { original_url, .. } => original_url,
+        }
+    }
+}
+
+const PHOTO_EXTENSIONS: &[&str] = &[".jpg", ".jpeg", ".png", ".gif", ".webp"];
+const AUDIO_EXTENSIONS: &[&str] = &[".mp3", ".ogg", ".opus", ".wav", ".m4a", ".flac"];
+
+fn has_extension(filename: &str, extensions: &[&str]) -> bool {
+    let filename = filename.to_lowercase();
+    extensions.iter().any(|ext| filename.ends_with(ext))
+}
+
+// cobalt names audio "Title - Author (service).mp3", that's all the metadata we get
+// sendAudio only takes mp3 and m4a, everything else has to go out as a document
+pub fn sends_as_audio(filename: &str, format: &AudioFormat) -> bool {
+    match filename.rsplit_once('.') {
+        Some((_, extension)) => matches!(extension.to_lowercase().as_str(), "mp3" | "m4a"),
+        None => *format == AudioFormat::Mp3,
+    }
+}
+
+pub fn audio_metadata(filename: &str) -> (Option<String>, Option<String>) {
+    let name = filename.rsplit_once('.').map_or(filename, |(name, _)| name);
+    let name = match name.rsplit_once(" (") {
+        Some((name, tail)) if tail.ends_with(')') => name,
+        _ => name,
+    };
+
+    match name.rsplit_once(" - ") {
+        Some((title, performer)) => (
+            Some(title.trim().to_string()),
+            Some(performer.trim().to_string()),
+        ),
+        None if !name.trim().is_empty() => (Some(name.trim().to_string()), None),
+        None => (None, None),
+    }
+}
+
+fn build_request(url: &str, settings: &CobaltSettings, mode: DownloadMode) -> DownloadRequest {
+    DownloadRequest {
         url: url.to_string(),
         filename_style: Some(FilenameStyle::Pretty),
         video_quality: Some(match settings.video_quality {
@@ -59,33 +256,70 @@ pub async fn resolve_download_url(
             VideoQuality::Q1440 => ccobalt::model::request::VideoQuality::Q1440,
             VideoQuality::Max => ccobalt::model::request::VideoQuality::Max,
         }),
+        download_mode: Some(mode),
+        audio_format: Some(match settings.audio_format {
+            AudioFormat::Best => ccobalt::model::request::AudioFormat::Best,
+            AudioFormat::Mp3 => ccobalt::model::request::AudioFormat::Mp3,
+            AudioFormat::Ogg => ccobalt::model::request::AudioFormat::Ogg,
+            AudioFormat::Opus => ccobalt::model::request::AudioFormat::Opus,
+        }),
+        audio_bitrate: Some(match settings.audio_bitrate {
+            AudioBitrate::K64 => ccobalt::model::request::AudioBitrate::K64,
+            AudioBitrate::K128 => ccobalt::model::request::AudioBitrate::K128,
+            AudioBitrate::K256 => ccobalt::model::request::AudioBitrate::K256,
+            AudioBitrate::K320 => ccobalt::model::request::AudioBitrate::K320,
+        }),
         ..Default::default()
-    };
-    let response = client.resolve_download(&cobalt_req).await?;
+    }
+}
+
+pub async fn resolve_download_url(
+    url: &str,
+    settings: &CobaltSettings,
+    pool: &CobaltPool,
+) -> Result<Option<DownloadResult>, MyError> {
+    let cobalt_req = build_request(url, settings, DownloadMode::Auto);
+    let response = pool.resolve_download(&cobalt_req).await?;
     match response {
         DownloadResponse::Error { error } => {
             log::error!("Cobalt API error: {:?}", error);
             Err(error.into())
         }
         DownloadResponse::Picker { picker, .. } => {
-            let photo_urls: Vec<String> = picker
+            let mut items: Vec<MediaItem> = picker
                 .iter()
-                .filter(|item| item.kind == "photo")
-                .map(|item| item.url.clone())
+                .filter_map(|item| {
+                    let kind = match item.kind.as_str() {
+                        "photo" => MediaKind::Photo,
+                        "video" | "gif" => MediaKind::Video,
+                        _ => return None,
+                    };
+                    Some(MediaItem {
+                        kind,
+                        url: item.url.clone(),
+                    })
+                })
                 .collect();
-            if !photo_urls.is_empty() {
+
+            if items.iter().all(|item| item.kind == MediaKind::Photo) {
+                if items.is_empty() {
+                    return Ok(None);
+                }
                 return Ok(Some(DownloadResult::Photos {
-                    urls: photo_urls,
+                    urls: items.into_iter().map(|item| item.url).collect(),
                     original_url: url.to_string(),
                 }));
             }
-            if let Some(video_item) = picker.iter().find(|item| item.kind == "video") {
+            if items.len() == 1 {
                 return Ok(Some(DownloadResult::Video {
-                    url: video_item.url.clone(),
+                    url: items.remove(0).url,
                     original_url: url.to_string(),
                 }));
             }
-            Ok(None)
+            Ok(Some(DownloadResult::Gallery {
+                items,
+                original_url: url.to_string(),
+            }))
         }
         DownloadResponse::Tunnel {
             url: c_url,
@@ -95,16 +329,18 @@ pub async fn resolve_download_url(
             url: c_url,
             filename,
         } => {
-            const PHOTO_EXTENSIONS: &[&str] = &[".jpg", ".jpeg", ".png", ".gif", ".webp"];
-            let is_photo = PHOTO_EXTENSIONS
-                .iter()
-                .any(|ext| filename.to_lowercase().ends_with(ext));
-
-            if is_photo {
+            if has_extension(&filename, PHOTO_EXTENSIONS) {
                 Ok(Some(DownloadResult::Photos {
                     urls: vec![c_url.clone()],
                     original_url: url.to_string(),
                 }))
+            } else if has_extension(&filename, AUDIO_EXTENSIONS) {
+                // soundcloud and friends have nothing but audio
+                Ok(Some(DownloadResult::Audio {
+                    url: c_url,
+                    original_url: url.to_string(),
+                    filename,
+                }))
             } else {
                 Ok(Some(DownloadResult::Video {
                     url: c_url,
@@ -120,3 +356,41 @@ pub async fn resolve_download_url(
             })),
     }
 }
+
+pub async fn resolve_audio_url(
+    url: &str,
+    settings: &CobaltSettings,
+    pool: &CobaltPool,
+) -> Result<Option<DownloadResult>, MyError> {
+    let cobalt_req = build_request(url, settings, DownloadMode::Audio);
+    let response = pool.resolve_download(&cobalt_req).await?;
+    match response {
+        DownloadResponse::Error { error } => {
+            log::error!("Cobalt API error: {:?}", error);
+            Err(error.into())
+        }
+        // slideshows come with their soundtrack next to the photos
+        DownloadResponse::Picker {
+            audio: Some(audio_url),
+            audio_filename,
+            ..
+        } => Ok(Some(DownloadResult::Audio {
+            url: audio_url,
+            original_url: url.to_string(),
+            filename: audio_filename.unwrap_or_default(),
+        })),
+        DownloadResponse::Tunnel {
+            url: c_url,
+            filename,
+        }
+        | DownloadResponse::Redirect {
+            url: c_url,
+            filename,
+        } => Ok(Some(DownloadResult::Audio {
+            url: c_url,
+            original_url: url.to_string(),
+            filename,
+        })),
+        _ => Ok(None),
+    }
+}
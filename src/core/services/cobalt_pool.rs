@@ -0,0 +1,255 @@
+use crate::{
+    core::config::{
+        Config,
+        json::{CobaltConfig, CobaltInstanceConfig},
+    },
+    errors::MyError,
+};
+use ccobalt::model::{request::DownloadRequest, response::DownloadResponse};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::time::{MissedTickBehavior, interval};
+
+// the instance is out of capacity or can't reach the service, another one may still manage.
+// anything else (unsupported link, private post...) would fail everywhere the same way
+const FAILOVER_ERRORS: [&str; 7] = [
+    "error.api.rate_exceeded",
+    "error.api.capacity",
+    "error.api.timed_out",
+    "error.api.unreachable",
+    "error.api.generic",
+    "error.api.auth.",
+    "error.api.fetch.",
+];
+
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct InstanceState {
+    healthy: bool,
+    resting_until: Option<Instant>,
+    current_weight: i64,
+}
+
+impl Default for InstanceState {
+    fn default() -> Self {
+        Self {
+            healthy: true,
+            resting_until: None,
+            current_weight: 0,
+        }
+    }
+}
+
+impl InstanceState {
+    fn is_available(&self, now: Instant) -> bool {
+        self.healthy && self.resting_until.is_none_or(|until| until <= now)
+    }
+}
+
+// Config is built for every update, so what the pool learns about instances has to outlive it
+static STATES: Lazy<Mutex<HashMap<String, InstanceState>>> = Lazy::new(Default::default);
+
+fn with_state<T>(url: &str, f: impl FnOnce(&mut InstanceState) -> T) -> T {
+    let mut states = STATES.lock().unwrap_or_else(|e| e.into_inner());
+    f(states.entry(url.to_string()).or_default())
+}
+
+fn is_failover_error(error: &impl std::fmt::Debug) -> bool {
+    let rendered = format!("{:?}", error);
+    FAILOVER_ERRORS.iter().any(|code| rendered.contains(code))
+}
+
+#[derive(Clone)]
+struct CobaltInstance {
+    url: String,
+    weight: u32,
+    client: ccobalt::Client,
+}
+
+impl CobaltInstance {
+    fn new(config: &CobaltInstanceConfig, user_agent: &str) -> Result<Self, String> {
+        let mut builder = ccobalt::Client::builder()
+            .base_url(config.url.clone())
+            .user_agent(user_agent.to_string());
+        if let Some(var) = &config.api_key_env {
+            let api_key = std::env::var(var)
+                .map_err(|_| format!("{} expected for cobalt instance {}", var, config.url))?;
+            builder = builder.api_key(api_key);
+        }
+        let client = builder
+            .build()
+            .map_err(|_| format!("Failed to build cobalt client for {}", config.url))?;
+
+        Ok(Self {
+            url: config.url.clone(),
+            weight: config.weight,
+            client,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct CobaltPool {
+    instances: Arc<Vec<CobaltInstance>>,
+    cooldown: Duration,
+}
+
+impl CobaltPool {
+    pub fn new(config: &CobaltConfig) -> Result<Self, String> {
+        if config.instances.is_empty() {
+            return Err("No cobalt instances configured".to_string());
+        }
+        let instances = config
+            .instances
+            .iter()
+            .map(|instance| CobaltInstance::new(instance, &config.user_agent))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            instances: Arc::new(instances),
+            cooldown: Duration::from_secs(config.cooldown_secs),
+        })
+    }
+
+    // smooth weighted round-robin decides who goes first, the other available instances follow
+    // by weight, and resting ones are still tried last rather than failing outright
+    fn candidates(&self) -> Vec<&CobaltInstance> {
+        let now = Instant::now();
+        let mut states = STATES.lock().unwrap_or_else(|e| e.into_inner());
+
+        let (mut available, mut resting): (Vec<&CobaltInstance>, Vec<&CobaltInstance>) =
+            self.instances.iter().partition(|instance| {
+                states
+                    .entry(instance.url.clone())
+                    .or_default()
+                    .is_available(now)
+            });
+
+        let total: i64 = available
+            .iter()
+            .map(|instance| instance.weight as i64)
+            .sum();
+        if total > 0 {
+            for instance in available.iter().filter(|instance| instance.weight > 0) {
+                states
+                    .entry(instance.url.clone())
+                    .or_default()
+                    .current_weight += instance.weight as i64;
+            }
+            let first = available
+                .iter()
+                .filter(|instance| instance.weight > 0)
+                .max_by_key(|instance| states[&instance.url].current_weight)
+                .map(|instance| instance.url.clone());
+            if let Some(first) = first {
+                states.entry(first.clone()).or_default().current_weight -= total;
+                available
+                    .sort_by_key(|instance| (instance.url != first, u32::MAX - instance.weight));
+            }
+        }
+
+        resting.sort_by_key(|instance| {
+            let state = &states[&instance.url];
+            (!state.healthy, state.resting_until)
+        });
+        available.extend(resting);
+        available
+    }
+
+    fn rest(&self, url: &str) {
+        let until = Instant::now() + self.cooldown;
+        with_state(url, |state| state.resting_until = Some(until));
+    }
+
+    pub async fn resolve_download(
+        &self,
+        request: &DownloadRequest,
+    ) -> Result<DownloadResponse, MyError> {
+        let mut last_error = None;
+
+        for instance in self.candidates() {
+            match instance.client.resolve_download(request).await {
+                Ok(DownloadResponse::Error { error }) if is_failover_error(&error) => {
+                    warn!(
+                        "Cobalt instance {} failed with {:?}, trying the next one",
+                        instance.url, error
+                    );
+                    self.rest(&instance.url);
+                    last_error = Some(MyError::from(error));
+                }
+                Err(e) => {
+                    warn!(
+                        "Cobalt instance {} is unreachable: {}, trying the next one",
+                        instance.url, e
+                    );
+                    self.rest(&instance.url);
+                    last_error = Some(MyError::from(e));
+                }
+                Ok(response) => {
+                    with_state(&instance.url, |state| {
+                        state.healthy = true;
+                        state.resting_until = None;
+                    });
+                    return Ok(response);
+                }
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| MyError::Other("No cobalt instances available".to_string())))
+    }
+
+    // the root of a cobalt instance answers with its version info
+    async fn check_instance(http: &Client, url: &str) -> bool {
+        let response = match http.get(url).timeout(HEALTH_CHECK_TIMEOUT).send().await {
+            Ok(response) if response.status().is_success() => response,
+            _ => return false,
+        };
+        response
+            .json::<serde_json::Value>()
+            .await
+            .is_ok_and(|info| info.get("cobalt").is_some())
+    }
+
+    pub async fn check_health(&self) {
+        let http = Client::new();
+        for instance in self.instances.iter() {
+            let healthy = Self::check_instance(&http, &instance.url).await;
+            let changed = with_state(&instance.url, |state| {
+                std::mem::replace(&mut state.healthy, healthy) != healthy
+            });
+            if changed {
+                if healthy {
+                    info!("Cobalt instance {} is back up", instance.url);
+                } else {
+                    warn!("Cobalt instance {} failed its health check", instance.url);
+                }
+            }
+        }
+    }
+}
+
+pub async fn run_cobalt_health_checks(config: Arc<Config>) {
+    let period = Duration::from_secs(
+        config
+            .get_json_config()
+            .get_cobalt_config()
+            .health_check_interval_secs
+            .max(10),
+    );
+    info!("Cobalt health checks started, every {:?}", period);
+
+    let mut interval = interval(period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+        config.get_cobalt_pool().check_health().await;
+    }
+}
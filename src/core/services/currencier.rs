@@ -4,7 +4,7 @@ use crate::{
             functions::get_or_create,
             schemas::{BaseFunctions, CurrenciesFunctions, group::Group, user::User},
         },
-        services::currency::converter::{CURRENCY_CONFIG_PATH, get_all_currency_codes},
+        services::currency::converter::get_all_currency_codes,
     },
     errors::MyError,
 };
@@ -23,7 +23,7 @@ pub async fn handle_currency_update<T: BaseFunctions + CurrenciesFunctions + Mod
 ) -> Result<(), MyError> {
     let code = code.to_uppercase();
 
-    let all_codes = get_all_currency_codes(CURRENCY_CONFIG_PATH.parse().unwrap())?;
+    let all_codes = get_all_currency_codes();
     if !all_codes.iter().any(|c| c.code == code) {
         let message = format!("Currency code <code>{}</code> does not exist.", code);
         bot.send_message(msg.chat.id, message)
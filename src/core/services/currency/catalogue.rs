@@ -0,0 +1,180 @@
+use crate::core::services::currency::converter::{
+    CURRENCY_CONFIG_PATH, CurrencyStruct, build_currency_regex, build_expression_regex,
+    build_target_regex,
+};
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+const KNOWN_SOURCES: [&str; 4] = ["coinbase", "tonapi", "metal", "stock"];
+const SOURCES_WITH_API_IDENTIFIER: [&str; 3] = ["tonapi", "metal", "stock"];
+
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+// everything that is derived from currencies.json, swapped as a whole on reload
+pub struct CurrencyCatalogue {
+    pub generation: u64,
+    pub currencies: Vec<CurrencyStruct>,
+    pub currency_info: HashMap<String, CurrencyStruct>,
+    pub currency_regex: Regex,
+    pub expression_regex: Regex,
+    pub target_regex: Regex,
+}
+
+fn entry_name(index: usize, code: Option<&str>) -> String {
+    match code.filter(|code| !code.is_empty()) {
+        Some(code) => format!("#{} ({})", index + 1, code),
+        None => format!("#{}", index + 1),
+    }
+}
+
+fn validate_currency(currency: &CurrencyStruct) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if currency.code.is_empty()
+        || !currency.code.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    {
+        problems.push("code должен состоять из заглавных латинских букв и цифр".to_string());
+    }
+    if !KNOWN_SOURCES.contains(&currency.source.as_str()) {
+        problems.push(format!(
+            "неизвестный source \"{}\", ожидается один из: {}",
+            currency.source,
+            KNOWN_SOURCES.join(", ")
+        ));
+    }
+    if SOURCES_WITH_API_IDENTIFIER.contains(&currency.source.as_str())
+        && currency.api_identifier.as_deref().is_none_or(|id| id.trim().is_empty())
+    {
+        problems.push(format!("для source \"{}\" нужен api_identifier", currency.source));
+    }
+    if currency.patterns.is_empty() {
+        problems.push("пустой список patterns".to_string());
+    }
+    if currency.patterns.iter().any(|pattern| pattern.trim().is_empty()) {
+        problems.push("пустая строка в patterns".to_string());
+    }
+    for (field, value) in [
+        ("one", &currency.one),
+        ("few", &currency.few),
+        ("many", &currency.many),
+        ("one_en", &currency.one_en),
+        ("many_en", &currency.many_en),
+    ] {
+        if value.trim().is_empty() {
+            problems.push(format!("пустое поле {}", field));
+        }
+    }
+
+    problems
+}
+
+impl CurrencyCatalogue {
+    // every problem is collected instead of stopping at the first one, so a single reload shows them all
+    pub fn load(path: &str) -> Result<Self, Vec<String>> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| vec![format!("не удалось прочитать {}: {}", path, e)])?;
+        let entries: Vec<serde_json::Value> = serde_json::from_str(&content)
+            .map_err(|e| vec![format!("{} не является JSON-массивом: {}", path, e)])?;
+
+        let mut issues = Vec::new();
+        let mut currencies = Vec::new();
+        let mut seen_codes = HashSet::new();
+
+        for (index, entry) in entries.into_iter().enumerate() {
+            let code = entry.get("code").and_then(|code| code.as_str()).map(str::to_string);
+            let name = entry_name(index, code.as_deref());
+
+            let currency: CurrencyStruct = match serde_json::from_value(entry) {
+                Ok(currency) => currency,
+                Err(e) => {
+                    issues.push(format!("{}: {}", name, e));
+                    continue;
+                }
+            };
+
+            let problems = validate_currency(&currency);
+            if !problems.is_empty() {
+                issues.extend(problems.into_iter().map(|problem| format!("{}: {}", name, problem)));
+                continue;
+            }
+            if !seen_codes.insert(currency.code.clone()) {
+                issues.push(format!("{}: код {} уже встречался выше", name, currency.code));
+                continue;
+            }
+
+            currencies.push(currency);
+        }
+
+        if currencies.is_empty() && issues.is_empty() {
+            issues.push(format!("{} не содержит ни одной валюты", path));
+        }
+        if !issues.is_empty() {
+            return Err(issues);
+        }
+
+        let compile = |name: &str, pattern: String| {
+            Regex::new(&pattern).map_err(|e| format!("регулярное выражение {}: {}", name, e))
+        };
+        let currency_regex = compile("валют", build_currency_regex(&currencies));
+        let expression_regex = compile("выражений", build_expression_regex(&currencies));
+        let target_regex = compile("целевой валюты", build_target_regex(&currencies));
+
+        match (currency_regex, expression_regex, target_regex) {
+            (Ok(currency_regex), Ok(expression_regex), Ok(target_regex)) => {
+                let currency_info = currencies
+                    .iter()
+                    .map(|currency| (currency.code.clone(), currency.clone()))
+                    .collect();
+
+                Ok(Self {
+                    generation: GENERATION.fetch_add(1, Ordering::Relaxed) + 1,
+                    currencies,
+                    currency_info,
+                    currency_regex,
+                    expression_regex,
+                    target_regex,
+                })
+            }
+            (currency_regex, expression_regex, target_regex) => Err([
+                currency_regex.err(),
+                expression_regex.err(),
+                target_regex.err(),
+            ]
+            .into_iter()
+            .flatten()
+            .collect()),
+        }
+    }
+}
+
+static CATALOGUE: Lazy<RwLock<Arc<CurrencyCatalogue>>> = Lazy::new(|| {
+    let catalogue = CurrencyCatalogue::load(CURRENCY_CONFIG_PATH).unwrap_or_else(|issues| {
+        panic!("FATAL: Invalid currency catalogue:\n{}", issues.join("\n"))
+    });
+    RwLock::new(Arc::new(catalogue))
+});
+
+pub fn catalogue() -> Arc<CurrencyCatalogue> {
+    CATALOGUE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+// the old catalogue stays in place if the file has any problem
+pub fn reload_catalogue() -> Result<Arc<CurrencyCatalogue>, Vec<String>> {
+    let catalogue = Arc::new(CurrencyCatalogue::load(CURRENCY_CONFIG_PATH)?);
+    *CATALOGUE.write().unwrap_or_else(|e| e.into_inner()) = catalogue.clone();
+    info!(
+        "Currency catalogue reloaded: {} currencies, generation {}",
+        catalogue.currencies.len(),
+        catalogue.generation
+    );
+    Ok(catalogue)
+}
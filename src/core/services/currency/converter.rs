@@ -1,17 +1,26 @@
 use crate::{
     bot::modules::{Owner, currency::CurrencySettings},
-    core::db::schemas::settings::Settings,
-    errors::MyError,
+    core::{
+        config::json::RatesConfig,
+        db::schemas::{rate_snapshot::RateSnapshot, settings::Settings},
+        services::currency::{
+            catalogue::catalogue,
+            expression,
+            language::OutputLanguage,
+            providers::{build_rate_providers, fetch_first_fresh},
+        },
+    },
     util::currency_values::WORD_VALUES,
 };
-use log::{debug, error, warn};
+use chrono::{NaiveDate, Utc};
+use log::{error, warn};
+use oximod::_error::oximod_error::OxiModError;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
-    fs,
+    collections::{BTreeMap, HashMap},
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -20,15 +29,6 @@ use tokio::sync::Mutex;
 
 const CACHE_DURATION_SECS: u64 = 60 * 10;
 pub const CURRENCY_CONFIG_PATH: &str = "currencies.json";
-const COINBASE_API_URL: &str = "https://api.coinbase.com/v2/exchange-rates?currency=UAH";
-const TONAPI_URL: &str = "https://tonapi.io/v2/rates";
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum OutputLanguage {
-    Russian,
-    #[allow(dead_code)]
-    English, // when
-}
 
 #[derive(Default)]
 struct ParseState {
@@ -43,7 +43,6 @@ pub enum ConvertError {
     #[error("Failed to parse JSON response: {0}")]
     ParseError(#[from] serde_json::Error),
     #[error("API returned an error: {0}")]
-    #[allow(dead_code)]
     ApiError(String),
     #[error("Currency '{0}' not found in the configuration")]
     CurrencyNotFound(String),
@@ -61,15 +60,14 @@ pub enum ConvertError {
     #[error("Failed to build regex from config: {0}")]
     #[allow(dead_code)]
     RegexBuildError(String),
+    #[error("Rate history error: {0}")]
+    HistoryError(#[from] OxiModError),
+    #[error("Rate history query error: {0}")]
+    HistoryQueryError(#[from] mongodb::error::Error),
 }
 
-fn build_regex_from_config() -> Result<String, ConvertError> {
-    let config_content = fs::read_to_string(CURRENCY_CONFIG_PATH)
-        .map_err(|e| ConvertError::ConfigFileReadError(CURRENCY_CONFIG_PATH.to_string(), e))?;
-
-    let currencies: Vec<CurrencyStruct> = serde_json::from_str(&config_content)
-        .map_err(|e| ConvertError::ConfigFileParseError(CURRENCY_CONFIG_PATH.to_string(), e))?;
-
+// (patterns, symbols) alternations for every currency in the catalogue
+fn build_identifier_parts(currencies: &[CurrencyStruct]) -> (String, String) {
     let mut all_patterns = Vec::new();
     let mut all_symbols = Vec::new();
 
@@ -83,8 +81,13 @@ fn build_regex_from_config() -> Result<String, ConvertError> {
     let escaped_patterns: Vec<String> = all_patterns.iter().map(|p| regex::escape(p)).collect();
     let escaped_symbols: Vec<String> = all_symbols.iter().map(|s| regex::escape(s)).collect();
 
-    let patterns_part = escaped_patterns.join("|");
-    let symbols_part = escaped_symbols.join("|");
+    (escaped_patterns.join("|"), escaped_symbols.join("|"))
+}
+
+const NUMBER_SUFFIXES: &str = r"к|k|м|m|б|b|т|t|тыс|млн|млрд|трлн|kk|кк";
+
+pub(crate) fn build_currency_regex(currencies: &[CurrencyStruct]) -> String {
+    let (patterns_part, symbols_part) = build_identifier_parts(currencies);
 
     let multiplier_words_part: String = WORD_VALUES
         .iter()
@@ -95,7 +98,7 @@ fn build_regex_from_config() -> Result<String, ConvertError> {
 
     let number_words: Vec<String> = WORD_VALUES.keys().map(|s| regex::escape(s)).collect();
 
-    let number_suffixes = r"к|k|м|m|б|b|т|t|тыс|млн|млрд|трлн|kk|кк";
+    let number_suffixes = NUMBER_SUFFIXES;
     let repeatable_digits_part = format!(r"(?:[\d.,_ \t]*(?:[ \t]*(?:{number_suffixes}))?)+");
 
     let number_pattern_any = format!(
@@ -104,7 +107,7 @@ fn build_regex_from_config() -> Result<String, ConvertError> {
         number_words.join("|")
     );
 
-    let regex_string = format!(
+    format!(
         concat!(
             r"(?i)(?:^|\s)(?:",
             r"({digits})[ \t]+({multiplier})[ \t]*({word_patterns})\b",
@@ -121,271 +124,222 @@ fn build_regex_from_config() -> Result<String, ConvertError> {
         number = number_pattern_any,
         word_patterns = patterns_part,
         symbols = symbols_part
+    )
+}
+
+// at least one operator between two operands, so plain amounts still go through the currency regex
+pub(crate) fn build_expression_regex(currencies: &[CurrencyStruct]) -> String {
+    let (patterns_part, symbols_part) = build_identifier_parts(currencies);
+
+    // "1 500/4 евро" is 1500/4, a space before exactly three digits is a thousands separator
+    let operand = format!(
+        r"\d[\d.,_]*(?:[ \t]\d{{3}}(?:[.,]\d+)?)*(?:(?:{})\d*)*",
+        NUMBER_SUFFIXES
+    );
+    let expression = format!(
+        r"\(*[ \t]*{operand}(?:[ \t]*\)*[ \t]*[-+*/×÷][ \t]*\(*[ \t]*{operand})+[ \t]*\)*",
     );
 
-    Ok(regex_string)
+    format!(
+        concat!(
+            r"(?i)(?:^|\s)(?:",
+            r"({expression})[ \t]*({word_patterns})\b",
+            r"|",
+            r"({expression})[ \t]*({symbols})",
+            r"|",
+            r"({symbols})[ \t]*({expression})",
+            r")",
+        ),
+        expression = expression,
+        word_patterns = patterns_part,
+        symbols = symbols_part
+    )
 }
 
-pub(crate) static CURRENCY_REGEX: Lazy<Regex> = Lazy::new(|| {
-    let regex_string = build_regex_from_config()
-        .map_err(|e| e.to_string())
-        .expect("FATAL: Could not build regex from currency config file.");
-    Regex::new(&regex_string)
-        .unwrap_or_else(|e| panic!("FATAL: Invalid regex generated from config: {}", e))
-});
+// "100 usd в jpy", "5k грн to $", "1 ton -> usd"
+pub(crate) fn build_target_regex(currencies: &[CurrencyStruct]) -> String {
+    let (patterns_part, symbols_part) = build_identifier_parts(currencies);
+
+    format!(
+        concat!(
+            r"(?i)(?:(?:^|\s)(?:в|во|to|in|into)[ \t]+|[ \t]*(?:->|→|=>)[ \t]*)",
+            r"(?:({word_patterns})\b|({symbols}))",
+        ),
+        word_patterns = patterns_part,
+        symbols = symbols_part
+    )
+}
 
 // for combined values like 2k2k2k2k ton
+// aliases only count as whole words, "ус" must not fire inside "успех"
+pub fn build_alias_regex(aliases: &CurrencyAliases) -> Option<Regex> {
+    if aliases.is_empty() {
+        return None;
+    }
+
+    let mut words: Vec<&String> = aliases.keys().collect();
+    words.sort_by_key(|word| std::cmp::Reverse(word.chars().count()));
+    let alternation = words
+        .iter()
+        .map(|word| regex::escape(word))
+        .collect::<Vec<_>>()
+        .join("|");
+
+    Regex::new(&format!(r"(?i)(^|[^\p{{L}}])({})\b", alternation)).ok()
+}
+
 static COMPONENT_RE: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(\d+(?:[.,]\d+)?)\s*(кк|kk|k|к|m|м|b|б|t|т|тыс|млн|млрд|трлн)").unwrap()
 });
 static INFIX_K_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^(\d+(?:[.,]\d+)?)[kк](\d{1,3})$").unwrap());
+// 01.02.2024, 01/02/2024 or 2024-02-01
+static DATE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(?:(\d{1,2})[./](\d{1,2})[./](\d{4})|(\d{4})-(\d{1,2})-(\d{1,2}))\b").unwrap()
+});
 
-pub fn get_all_currency_codes(config_file: String) -> Result<Vec<CurrencyStruct>, ConvertError> {
-    let mut codes: Vec<CurrencyStruct> = vec![];
-
-    let config_content = fs::read_to_string(config_file.clone())
-        .map_err(|e| ConvertError::ConfigFileReadError(config_file.to_string(), e))?;
-    let currencies: Vec<CurrencyStruct> = serde_json::from_str(&config_content)
-        .map_err(|e| ConvertError::ConfigFileParseError(config_file.to_string(), e))?;
-
-    currencies
-        .iter()
-        .for_each(|currency| codes.push(currency.clone()));
-
-    Ok(codes)
+// the date is cut out so its digits don't end up glued to an amount
+fn extract_date(text: &str) -> (Option<NaiveDate>, String) {
+    let date = DATE_RE.captures(text).and_then(|cap| {
+        let (day, month, year) = match (cap.get(1), cap.get(2), cap.get(3)) {
+            (Some(day), Some(month), Some(year)) => (day, month, year),
+            _ => (cap.get(6)?, cap.get(5)?, cap.get(4)?),
+        };
+        NaiveDate::from_ymd_opt(
+            year.as_str().parse().ok()?,
+            month.as_str().parse().ok()?,
+            day.as_str().parse().ok()?,
+        )
+    });
+
+    (date, DATE_RE.replace_all(text, " ").into_owned())
 }
 
-pub fn get_default_currencies() -> Result<Vec<CurrencyStruct>, MyError> {
-    let all_codes = get_all_currency_codes(CURRENCY_CONFIG_PATH.parse().unwrap())?;
+pub fn get_all_currency_codes() -> Vec<CurrencyStruct> {
+    catalogue().currencies.clone()
+}
 
-    let necessary_codes = all_codes
+pub fn get_default_currencies() -> Vec<CurrencyStruct> {
+    get_all_currency_codes()
         .into_iter()
         .filter(|c| {
             ["uah", "rub", "usd", "byn", "eur", "ton"].contains(&c.code.to_lowercase().as_str())
         })
-        .collect::<Vec<CurrencyStruct>>();
-
-    Ok(necessary_codes)
+        .collect()
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct DetectedCurrency {
     amount: f64,
     currency_code: String,
+    date: Option<NaiveDate>,
+    target: Option<String>, // explicit "в jpy" replaces the selected codes for the whole message
+    expression: Option<String>, // echoed back when the amount was calculated
 }
 
 #[derive(Debug, Clone)]
 struct CachedRates {
     fetched_at: Instant,
+    catalogue_generation: u64,
     rates: HashMap<String, f64>,
-    #[allow(dead_code)]
     base_code: String,
 }
 
 type Cache = Arc<Mutex<Option<CachedRates>>>;
 
-#[derive(Deserialize, Debug)]
-struct CoinbaseResponse {
-    data: CoinbaseData,
-}
-#[derive(Deserialize, Debug)]
-struct CoinbaseData {
-    currency: String,
-    rates: HashMap<String, String>,
-}
-#[derive(Deserialize, Debug)]
-struct TonApiResponse {
-    rates: HashMap<String, TonRateEntry>,
-}
-#[derive(Deserialize, Debug, Clone)]
-struct TonRateEntry {
-    prices: HashMap<String, f64>,
-}
-
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CurrencyStruct {
     pub code: String,
     pub source: String,
     #[serde(default)]
     pub api_identifier: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coingecko_id: Option<String>,
     pub symbol: String,
     pub flag: String,
     pub patterns: Vec<String>,
     pub one: String,
     pub few: String,
     pub many: String,
-    #[allow(dead_code)]
     pub one_en: String,
-    #[allow(dead_code)]
     pub many_en: String,
     pub is_target: bool,
 }
 
+// custom word (lowercase) -> currency code, set per owner in the currency module settings
+pub type CurrencyAliases = BTreeMap<String, String>;
+
 pub struct CurrencyConverter {
     cache: Cache,
-    client: Client,
-    currency_info: HashMap<String, CurrencyStruct>,
     // target_currencies: Vec<String>,
-    #[allow(dead_code)]
-    language: OutputLanguage, // when
 
-    // for fucking ton api
-    ton_tickers: Vec<String>,
-    ton_addresses: Vec<String>,
-    ton_ticker_to_code: HashMap<String, String>, // <"ton", "TON">
-    ton_address_to_code: HashMap<String, String>, // <"EQ..NOT", "NOT">
-}
-
-fn get_plural_form(number: u64, one: &str, few: &str, many: &str) -> String {
-    let last_two_digits = number % 100;
-    let last_digit = number % 10;
-    if (11..=19).contains(&last_two_digits) {
-        many.to_string()
-    } else if last_digit == 1 {
-        one.to_string()
-    } else if (2..=4).contains(&last_digit) {
-        few.to_string()
-    } else {
-        many.to_string()
-    }
+    rates_config: RatesConfig,
+    client: Client,
+    max_rate_age: Duration,
 }
 
 impl CurrencyConverter {
-    pub fn new(language: OutputLanguage) -> Result<Self, ConvertError> {
-        let config_path_str = CURRENCY_CONFIG_PATH;
-        let config_content = fs::read_to_string(config_path_str)
-            .map_err(|e| ConvertError::ConfigFileReadError(config_path_str.to_string(), e))?;
-        let currencies: Vec<CurrencyStruct> = serde_json::from_str(&config_content)
-            .map_err(|e| ConvertError::ConfigFileParseError(config_path_str.to_string(), e))?;
-
-        let mut currency_map = HashMap::new();
-
-        // for fucking ton api
-        let mut ton_tickers = Vec::new();
-        let mut ton_addresses = Vec::new();
-        let mut ton_ticker_to_code = HashMap::new();
-        let mut ton_address_to_code = HashMap::new();
-
-        for currency in currencies {
-            if currency.source == "tonapi"
-                && let Some(identifier) = &currency.api_identifier
-            {
-                if identifier.len() > 10
-                    && (identifier.starts_with("EQ") || identifier.starts_with("UQ"))
-                {
-                    ton_addresses.push(identifier.clone());
-                    ton_address_to_code.insert(identifier.clone(), currency.code.clone());
-                } else {
-                    let lower_ticker = identifier.to_lowercase();
-                    ton_tickers.push(lower_ticker.clone());
-                    ton_ticker_to_code.insert(lower_ticker, currency.code.clone());
-                }
-            }
-            currency_map.insert(currency.code.clone(), currency);
-        }
-
-        Ok(CurrencyConverter {
+    pub fn new(rates_config: &RatesConfig) -> Self {
+        CurrencyConverter {
             cache: Arc::new(Mutex::new(None)),
+            rates_config: rates_config.clone(),
             client: Client::new(),
-            currency_info: currency_map,
-            language,
-            ton_tickers,
-            ton_addresses,
-            ton_ticker_to_code,
-            ton_address_to_code,
-        })
-    }
-
-    async fn fetch_crypto_rates(&self) -> Result<HashMap<String, f64>, ConvertError> {
-        let all_tokens = [self.ton_tickers.as_slice(), self.ton_addresses.as_slice()].concat();
-        if all_tokens.is_empty() {
-            return Ok(HashMap::new());
+            max_rate_age: Duration::from_secs(rates_config.max_age_hours * 60 * 60),
         }
-
-        let tokens_str = all_tokens.join(",");
-        let response = self
-            .client
-            .get(TONAPI_URL)
-            .query(&[("tokens", &tokens_str), ("currencies", &"uah".to_string())])
-            .send()
-            .await?;
-
-        let parsed = response.json::<TonApiResponse>().await?;
-
-        let mut crypto_rates = HashMap::new();
-
-        parsed
-            .rates
-            .iter()
-            .for_each(|(api_identifier, rate_entry)| {
-                let mut code: Option<String> = None;
-
-                if let Some(found_code) = self.ton_address_to_code.get(api_identifier) {
-                    code = Some(found_code.clone());
-                } else if let Some(found_code) =
-                    self.ton_ticker_to_code.get(&api_identifier.to_lowercase())
-                {
-                    code = Some(found_code.clone());
-                }
-
-                if let Some(found_code) = code {
-                    if let Some(price_in_uah) = rate_entry.prices.get("UAH") {
-                        crypto_rates.insert(found_code, *price_in_uah);
-                    }
-                } else {
-                    // ???
-                    debug!(
-                        "Skipped unknown API identifier from TonAPI: {}",
-                        api_identifier
-                    );
-                }
-            });
-        Ok(crypto_rates)
-    }
-
-    async fn fetch_fiat_rates(&self) -> Result<HashMap<String, f64>, ConvertError> {
-        let response = self.client.get(COINBASE_API_URL).send().await?;
-        let parsed = response.json::<CoinbaseResponse>().await?;
-        let mut rates = parsed
-            .data
-            .rates
-            .into_iter()
-            .filter_map(|(currency_code, rate_str)| {
-                rate_str.parse::<f64>().ok().and_then(|rate_val| {
-                    if rate_val != 0.0 {
-                        Some((currency_code, 1.0 / rate_val))
-                    } else {
-                        None
-                    }
-                })
-            })
-            .collect::<HashMap<String, f64>>();
-        rates.insert(parsed.data.currency, 1.0);
-        Ok(rates)
     }
 
     async fn fetch_rates(&self) -> Result<CachedRates, ConvertError> {
-        let (fiat_result, crypto_result) =
-            tokio::join!(self.fetch_fiat_rates(), self.fetch_crypto_rates());
+        // built from the current catalogue every time, so reloaded ton tickers apply right away
+        let catalogue = catalogue();
+        let fiat_providers = build_rate_providers(
+            &self.rates_config.fiat,
+            &self.rates_config,
+            &catalogue.currencies,
+            &self.client,
+        );
+        let token_providers = build_rate_providers(
+            &self.rates_config.tokens,
+            &self.rates_config,
+            &catalogue.currencies,
+            &self.client,
+        );
 
-        let mut combined_rates = fiat_result.map_err(|e| {
-            error!("CRITICAL: Failed to fetch vital fiat rates: {}", e);
-            e
-        })?;
+        let market_providers = build_rate_providers(
+            &self.rates_config.markets,
+            &self.rates_config,
+            &catalogue.currencies,
+            &self.client,
+        );
 
-        if let Ok(crypto_rates) = crypto_result {
-            combined_rates.extend(crypto_rates);
-        }
+        let (fiat_table, token_table, market_table) = tokio::join!(
+            fetch_first_fresh(&fiat_providers, self.max_rate_age),
+            fetch_first_fresh(&token_providers, self.max_rate_age),
+            fetch_first_fresh(&market_providers, self.max_rate_age)
+        );
 
-        if combined_rates.is_empty() {
+        let Some(mut table) = fiat_table else {
+            error!("CRITICAL: Failed to fetch vital fiat rates from every provider");
             return Err(ConvertError::NoRatesFetched);
+        };
+
+        for (kind, extra_table) in [("Token", token_table), ("Market", market_table)] {
+            if let Some(extra_table) = extra_table
+                && !table.merge(extra_table.clone())
+            {
+                let mut lost: Vec<&String> = extra_table.rates.keys().collect();
+                lost.sort();
+                error!(
+                    "{} rates ({} based) share no currency with the {} based fiat rates, no rates for {:?}",
+                    kind, extra_table.base_code, table.base_code, lost
+                );
+            }
         }
 
         Ok(CachedRates {
             fetched_at: Instant::now(),
-            rates: combined_rates,
-            base_code: "UAH".to_string(),
+            catalogue_generation: catalogue.generation,
+            rates: table.rates,
+            base_code: table.base_code,
         })
     }
 
@@ -393,16 +347,43 @@ impl CurrencyConverter {
         let mut cache_guard = self.cache.lock().await;
         if let Some(cached_data) = &*cache_guard
             && cached_data.fetched_at.elapsed() < Duration::from_secs(CACHE_DURATION_SECS)
+            && cached_data.catalogue_generation == catalogue().generation
         {
             return Ok(cached_data.clone());
         }
 
         let new_rates = self.fetch_rates().await?;
+        self.snapshot_rates(&new_rates).await;
 
         *cache_guard = Some(new_rates.clone());
         Ok(new_rates)
     }
 
+    // only the currencies we know about, coinbase alone returns a few hundred
+    async fn snapshot_rates(&self, rates_data: &CachedRates) {
+        let catalogue = catalogue();
+        let rates = rates_data
+            .rates
+            .iter()
+            .filter(|(code, _)| catalogue.currency_info.contains_key(*code))
+            .map(|(code, rate)| (code.clone(), *rate))
+            .collect();
+
+        if let Err(e) = RateSnapshot::record(Utc::now(), &rates_data.base_code, rates).await {
+            warn!("Failed to save rates snapshot: {}", e);
+        }
+    }
+
+    async fn get_rates_on(&self, date: NaiveDate) -> Result<Option<CachedRates>, ConvertError> {
+        let snapshot = RateSnapshot::latest_on(date).await?;
+        Ok(snapshot.map(|snapshot| CachedRates {
+            fetched_at: Instant::now(),
+            catalogue_generation: 0,
+            rates: snapshot.rates,
+            base_code: snapshot.base_code,
+        }))
+    }
+
     pub fn parse_number_words(text: &str) -> Option<f64> {
         let state = text
             .split_whitespace()
@@ -438,9 +419,50 @@ impl CurrencyConverter {
         }
     }
 
+    // custom words aren't part of the global regexes, so they are swapped for a known pattern first
+    fn apply_aliases(&self, text: &str, aliases: &CurrencyAliases) -> String {
+        let Some(alias_re) = build_alias_regex(aliases) else {
+            return text.to_string();
+        };
+
+        alias_re
+            .replace_all(text, |cap: &regex::Captures| {
+                let pattern = aliases
+                    .get(&cap[2].to_lowercase())
+                    .and_then(|code| self.find_currency_info(code))
+                    .and_then(|info| info.patterns.first().cloned());
+                match pattern {
+                    Some(pattern) => format!("{} {}", &cap[1], pattern),
+                    None => cap[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+
+    // the phrase is cut out so the target currency isn't read as one more amount
+    fn extract_target(&self, text: &str, aliases: &CurrencyAliases) -> (Option<String>, String) {
+        // "оценивается в $100" is an amount, not a target: the symbol belongs to the number after it
+        let Some(cap) = catalogue().target_regex.captures_iter(text).find(|cap| {
+            let rest = &text[cap.get(0).unwrap().end()..];
+            !rest.trim_start().starts_with(|c: char| c.is_ascii_digit())
+        }) else {
+            return (None, text.to_string());
+        };
+        let identifier = cap.get(1).or_else(|| cap.get(2)).map(|m| m.as_str().trim());
+        let Some(info) = identifier.and_then(|id| self.find_currency_info_by_identifier(id, aliases)) else {
+            return (None, text.to_string());
+        };
+
+        let whole = cap.get(0).unwrap();
+        let mut masked = text.to_string();
+        masked.replace_range(whole.range(), " ");
+        (Some(info.code.clone()), masked)
+    }
+
     pub fn parse_text_for_currencies(
         &self,
         text: &str,
+        aliases: &CurrencyAliases,
     ) -> Result<Vec<DetectedCurrency>, ConvertError> {
         let parse_amount_or_words = |amount_str: &str| -> Option<f64> {
             let first_char = amount_str.chars().next();
@@ -452,9 +474,53 @@ impl CurrencyConverter {
             }
         };
 
-        let currencies = CURRENCY_REGEX
-            .captures_iter(text)
+        let text = self.apply_aliases(text, aliases);
+        let (date, text) = extract_date(&text);
+        let (target, text) = self.extract_target(&text, aliases);
+
+        // expressions go first and get blanked out, otherwise their tail would be read as a plain amount
+        let mut detected = Vec::new();
+        let mut masked = text.clone();
+        let catalogue = catalogue();
+        for cap in catalogue.expression_regex.captures_iter(&text) {
+            let (expression, identifier) = match (cap.get(1), cap.get(2), cap.get(3), cap.get(4)) {
+                (Some(expression), Some(identifier), _, _) => (expression, identifier),
+                (_, _, Some(expression), Some(identifier)) => (expression, identifier),
+                _ => match (cap.get(5), cap.get(6)) {
+                    (Some(identifier), Some(expression)) => (expression, identifier),
+                    _ => continue,
+                },
+            };
+
+            let Some(amount) = expression::evaluate(expression.as_str(), Self::parse_amount_with_suffix)
+                .filter(|amount| *amount >= 0.0)
+            else {
+                continue;
+            };
+            let Some(info) = self.find_currency_info_by_identifier(identifier.as_str().trim(), aliases) else {
+                continue;
+            };
+
+            let whole = cap.get(0).unwrap();
+            // same byte length, so match positions in both passes stay comparable
+            masked.replace_range(whole.range(), &" ".repeat(whole.len()));
+            detected.push((
+                whole.start(),
+                DetectedCurrency {
+                    amount,
+                    currency_code: info.code.clone(),
+                    date,
+                    target: target.clone(),
+                    expression: Some(expression.as_str().trim().to_string()),
+                },
+            ));
+        }
+
+        let currencies = catalogue
+            .currency_regex
+            .captures_iter(&masked)
             .filter_map(|cap| {
+                let start = cap.get(0)?.start();
                 let (amount, identifier_str) =
                     // {num} {multiplier} {symbol}
                     if let (Some(num_str), Some(multiplier_match), Some(identifier)) =
@@ -496,16 +562,23 @@ impl CurrencyConverter {
                         None
                     }?;
 
-                let info = self.find_currency_info_by_identifier(identifier_str)?;
-
-                Some(DetectedCurrency {
-                    amount,
-                    currency_code: info.code.clone(),
-                })
-            })
-            .collect();
+                let info = self.find_currency_info_by_identifier(identifier_str, aliases)?;
+
+                Some((
+                    start,
+                    DetectedCurrency {
+                        amount,
+                        currency_code: info.code.clone(),
+                        date,
+                        target: target.clone(),
+                        expression: None,
+                    },
+                ))
+            });
+        detected.extend(currencies);
+        detected.sort_by_key(|(start, _)| *start);
 
-        Ok(currencies)
+        Ok(detected.into_iter().map(|(_, currency)| currency).collect())
     }
 
     pub fn parse_amount_with_suffix(amount_str: &str) -> Option<f64> {
@@ -593,18 +666,33 @@ impl CurrencyConverter {
             .or_else(|| number_part_str.parse::<f64>().ok())
     }
 
-    fn find_currency_info(&self, code: &str) -> Option<&CurrencyStruct> {
-        self.currency_info.get(code)
+    fn find_currency_info(&self, code: &str) -> Option<CurrencyStruct> {
+        catalogue().currency_info.get(code).cloned()
     }
 
-    fn find_currency_info_by_identifier(&self, identifier: &str) -> Option<&CurrencyStruct> {
+    pub fn find_currency_info_by_identifier(
+        &self,
+        identifier: &str,
+        aliases: &CurrencyAliases,
+    ) -> Option<CurrencyStruct> {
+        if let Some(info) = aliases
+            .get(&identifier.trim().to_lowercase())
+            .and_then(|code| self.find_currency_info(code))
+        {
+            return Some(info);
+        }
+
         let lower_identifier = identifier.to_lowercase().replace(['.', ' '], "");
-        self.currency_info.values().find(|info| {
-            info.patterns
-                .iter()
-                .any(|p| p.to_lowercase().replace(['.', ' '], "") == lower_identifier)
-                || info.symbol == identifier
-        })
+        catalogue()
+            .currencies
+            .iter()
+            .find(|info| {
+                info.patterns
+                    .iter()
+                    .any(|p| p.to_lowercase().replace(['.', ' '], "") == lower_identifier)
+                    || info.symbol == identifier
+            })
+            .cloned()
     }
 
     fn convert_amount(
@@ -632,23 +720,30 @@ impl CurrencyConverter {
         original: &DetectedCurrency,
         rates_data: &CachedRates,
         target_codes: &[String],
+        language: OutputLanguage,
     ) -> Result<String, ConvertError> {
         let mut result = String::new();
         let original_info = self
             .find_currency_info(&original.currency_code)
             .ok_or_else(|| ConvertError::CurrencyNotFound(original.currency_code.clone()))?;
 
-        let original_word = get_plural_form(
-            original.amount.trunc() as u64,
-            &original_info.one,
-            &original_info.few,
-            &original_info.many,
-        );
-
-        result.push_str(&format!(
-            "{} {:.2}{} {}\n\n",
-            original_info.flag, original.amount, original_info.symbol, original_word
-        ));
+        let original_amount = language.format_number(original.amount, 2);
+        let original_word = language.currency_word(original.amount, 2, &original_info);
+
+        match &original.expression {
+            Some(expression) => result.push_str(&format!(
+                "{} {} = {}{} {}",
+                original_info.flag, expression, original_amount, original_info.symbol, original_word
+            )),
+            None => result.push_str(&format!(
+                "{} {}{} {}",
+                original_info.flag, original_amount, original_info.symbol, original_word
+            )),
+        }
+        if let Some(date) = original.date {
+            result.push_str(&format!(" ({})", language.rate_on(date)));
+        }
+        result.push_str("\n\n");
 
         for target_code in target_codes {
             if target_code == &original.currency_code {
@@ -663,23 +758,22 @@ impl CurrencyConverter {
                     &rates_data.rates,
                 ) {
                     Ok(converted_amount) => {
-                        let word = get_plural_form(
-                            converted_amount.trunc() as u64,
-                            &target_info.one,
-                            &target_info.few,
-                            &target_info.many,
-                        );
+                        let word = language.currency_word(converted_amount, 5, &target_info);
 
                         result.push_str(&format!(
-                            "{} {:.5}{} {}\n",
-                            target_info.flag, converted_amount, target_info.symbol, word
+                            "{} {}{} {}\n",
+                            target_info.flag,
+                            language.format_number(converted_amount, 5),
+                            target_info.symbol,
+                            word
                         ));
                     }
                     Err(e) => {
                         warn!(
-                            "Conversion error from {} to {}: {}. Skipping.",
+                            "Conversion error from {} to {}: {}",
                             original.currency_code, target_code, e
                         );
+                        result.push_str(&format!("{} {}\n", target_info.flag, language.no_rate()));
                     }
                 }
             }
@@ -687,6 +781,44 @@ impl CurrencyConverter {
         Ok(result.trim_end().to_string())
     }
 
+    pub async fn get_pair_rate(&self, from_code: &str, to_code: &str) -> Result<f64, ConvertError> {
+        let rates_data = self.get_rates().await?;
+        Self::convert_amount(1.0, from_code, to_code, &rates_data.rates)
+    }
+
+    // every selected currency priced in the first one
+    pub async fn format_digest(
+        &self,
+        codes: &[String],
+        language: OutputLanguage,
+    ) -> Result<String, ConvertError> {
+        let Some(base_code) = codes.first() else {
+            return Ok(String::new());
+        };
+        let base_info = self
+            .find_currency_info(base_code)
+            .ok_or_else(|| ConvertError::CurrencyNotFound(base_code.clone()))?;
+        let rates_data = self.get_rates().await?;
+
+        let mut result = String::new();
+        for code in codes.iter().skip(1) {
+            let Some(info) = self.find_currency_info(code) else {
+                continue;
+            };
+            match Self::convert_amount(1.0, code, base_code, &rates_data.rates) {
+                Ok(rate) => result.push_str(&format!(
+                    "{} 1 {} = {}{}\n",
+                    info.flag,
+                    info.code,
+                    language.format_number(rate, 4),
+                    base_info.symbol
+                )),
+                Err(e) => warn!("Digest conversion error from {} to {}: {}", code, base_code, e),
+            }
+        }
+        Ok(result.trim_end().to_string())
+    }
+
     pub async fn process_text(
         &self,
         text: &str,
@@ -705,21 +837,40 @@ impl CurrencyConverter {
                 }
             };
 
-        if !currency_settings.enabled || currency_settings.selected_codes.is_empty() {
+        if !currency_settings.enabled {
             return Ok(Vec::new());
         }
 
-        let target_codes = currency_settings.selected_codes;
+        let language = currency_settings.language;
 
-        let detected_currencies = self.parse_text_for_currencies(text)?;
+        let detected_currencies = self.parse_text_for_currencies(text, &currency_settings.aliases)?;
         if detected_currencies.is_empty() {
             return Ok(Vec::new());
         }
 
-        let rates_data = self.get_rates().await?;
+        let target_codes = match &detected_currencies[0].target {
+            Some(target) => vec![target.clone()],
+            None => currency_settings.selected_codes,
+        };
+        if target_codes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // all amounts in one message share the same date, so one lookup is enough
+        // today's rates are the live ones anyway
+        let today = Utc::now().date_naive();
+        let rates_data = match detected_currencies[0].date {
+            Some(date) if date > today => return Ok(vec![language.future_date(date)]),
+            Some(date) if date < today => match self.get_rates_on(date).await? {
+                Some(rates_data) => rates_data,
+                None => return Ok(vec![language.no_rates_on(date)]),
+            },
+            _ => self.get_rates().await?,
+        };
+
         let mut results = Vec::new();
         for detected in detected_currencies {
-            match self.format_conversion_result(&detected, &rates_data, &target_codes) {
+            match self.format_conversion_result(&detected, &rates_data, &target_codes, language) {
                 Ok(formatted) => results.push(formatted),
                 Err(e) => error!("Error formatting conversion for {:?}: {}", detected, e),
             }
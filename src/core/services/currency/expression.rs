@@ -0,0 +1,207 @@
+// tiny recursive descent evaluator for amounts like "(25+17)*3" or "1500/4"
+// operands are handed to `parse_number`, so suffixes like 1k200 keep working inside expressions
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// "1 500" is one number, the space is only a thousands separator
+static THOUSANDS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d)[ \t]+(\d{3})(\D|$)").unwrap());
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Op(char),
+    Open,
+    Close,
+}
+
+fn tokenize(expr: &str, parse_number: &impl Fn(&str) -> Option<f64>) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut operand = String::new();
+
+    let flush = |operand: &mut String, tokens: &mut Vec<Token>| -> Option<()> {
+        if !operand.is_empty() {
+            tokens.push(Token::Number(parse_number(operand)?));
+            operand.clear();
+        }
+        Some(())
+    };
+
+    for c in expr.chars() {
+        match c {
+            '+' | '-' | '*' | '/' | '×' | '÷' => {
+                flush(&mut operand, &mut tokens)?;
+                let op = match c {
+                    '×' => '*',
+                    '÷' => '/',
+                    c => c,
+                };
+                tokens.push(Token::Op(op));
+            }
+            '(' => {
+                flush(&mut operand, &mut tokens)?;
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                flush(&mut operand, &mut tokens)?;
+                tokens.push(Token::Close);
+            }
+            c if c.is_whitespace() => flush(&mut operand, &mut tokens)?,
+            c => operand.push(c),
+        }
+    }
+    flush(&mut operand, &mut tokens)?;
+
+    Some(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Option<f64> {
+        let mut value = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Some(value)
+    }
+
+    // term := factor (('*' | '/') factor)*
+    fn term(&mut self) -> Option<f64> {
+        let mut value = self.factor()?;
+        while let Some(Token::Op(op @ ('*' | '/'))) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.factor()?;
+            if op == '/' && rhs == 0.0 {
+                return None;
+            }
+            value = if op == '*' { value * rhs } else { value / rhs };
+        }
+        Some(value)
+    }
+
+    // factor := number | '(' expr ')' | '-' factor
+    fn factor(&mut self) -> Option<f64> {
+        match self.next()? {
+            Token::Number(value) => Some(value),
+            Token::Op('-') => self.factor().map(|value| -value),
+            Token::Open => {
+                let value = self.expr()?;
+                (self.next()? == Token::Close).then_some(value)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn join_thousands(expr: &str) -> String {
+    // matches can't overlap, so "1 000 000" takes more than one pass
+    let mut joined = expr.to_string();
+    loop {
+        let next = THOUSANDS_RE.replace_all(&joined, "$1$2$3").into_owned();
+        if next == joined {
+            return joined;
+        }
+        joined = next;
+    }
+}
+
+pub fn evaluate(expr: &str, parse_number: impl Fn(&str) -> Option<f64>) -> Option<f64> {
+    let tokens = tokenize(&join_thousands(expr), &parse_number)?;
+    let mut parser = Parser { tokens, pos: 0 };
+
+    let value = parser.expr()?;
+    if parser.pos != parser.tokens.len() || !value.is_finite() {
+        return None;
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::services::currency::converter::{CurrencyStruct, build_expression_regex};
+
+    fn eval(expr: &str) -> Option<f64> {
+        evaluate(expr, |s| s.replace(',', ".").parse().ok())
+    }
+
+    #[test]
+    fn multiplication_binds_tighter_than_addition() {
+        assert_eq!(eval("2+3*4"), Some(14.0));
+        assert_eq!(eval("10-6/2"), Some(7.0));
+        assert_eq!(eval("(2+3)*4"), Some(20.0));
+        assert_eq!(eval("8/2/2"), Some(2.0));
+        assert_eq!(eval("10-2-3"), Some(5.0));
+        assert_eq!(eval("3 × 2 ÷ 4"), Some(1.5));
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_eq!(eval("-5+10"), Some(5.0));
+        assert_eq!(eval("10*-2"), Some(-20.0));
+        assert_eq!(eval("-(2+3)"), Some(-5.0));
+        assert_eq!(eval("--4"), Some(4.0));
+    }
+
+    #[test]
+    fn division_by_zero_is_rejected() {
+        assert_eq!(eval("5/0"), None);
+        assert_eq!(eval("5/(2-2)"), None);
+    }
+
+    #[test]
+    fn unbalanced_parentheses_are_rejected() {
+        assert_eq!(eval("(2+3"), None);
+        assert_eq!(eval("2+3)"), None);
+        assert_eq!(eval("((2+3)*4"), None);
+        assert_eq!(eval("()"), None);
+    }
+
+    #[test]
+    fn dangling_operators_are_rejected() {
+        assert_eq!(eval("2+"), None);
+        assert_eq!(eval("*2"), None);
+        assert_eq!(eval("2 3"), None);
+    }
+
+    #[test]
+    fn spaces_separate_thousands() {
+        assert_eq!(eval("1 500/4"), Some(375.0));
+        assert_eq!(eval("1 000 000 + 1"), Some(1_000_001.0));
+        assert_eq!(eval("2 * 1 500,5"), Some(3001.0));
+        assert_eq!(eval("12 34"), None);
+    }
+
+    #[test]
+    fn expression_regex_keeps_thousands_together() {
+        let euro: CurrencyStruct = serde_json::from_value(serde_json::json!({
+            "code": "EUR", "source": "test", "symbol": "€", "flag": "🇪🇺",
+            "patterns": ["евро"], "one": "евро", "few": "евро", "many": "евро",
+            "one_en": "euro", "many_en": "euros", "is_target": true
+        }))
+        .unwrap();
+        let re = Regex::new(&build_expression_regex(&[euro])).unwrap();
+
+        let cap = re.captures("1 500/4 евро").unwrap();
+        assert_eq!(cap.get(1).map(|m| m.as_str().trim()), Some("1 500/4"));
+        let cap = re.captures("всего 2 000 000 - 500 евро").unwrap();
+        assert_eq!(cap.get(1).map(|m| m.as_str().trim()), Some("2 000 000 - 500"));
+    }
+}
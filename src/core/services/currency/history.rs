@@ -0,0 +1,157 @@
+use crate::{
+    core::{
+        db::schemas::rate_snapshot::RateSnapshot,
+        services::{
+            chart::{ChartPoint, render_line_chart},
+            currency::converter::{CurrencyAliases, CurrencyConverter, CurrencyStruct},
+        },
+    },
+    errors::MyError,
+};
+use chrono::{DateTime, Days, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+const MAX_CHART_DAYS: u32 = 365;
+const MAX_CHART_POINTS: usize = 300;
+
+static PERIOD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)^(\d{1,3})([dwmyднмг]?)$").unwrap());
+
+pub const CHART_USAGE: &str = "Использование: <code>/chart USD UAH 30d</code>\n\
+    Период: <code>1d</code>, <code>7d</code>, <code>2w</code>, <code>3m</code>, <code>1y</code> (по умолчанию 30 дней).";
+
+pub struct ChartRequest {
+    pub from: CurrencyStruct,
+    pub to: CurrencyStruct,
+    pub days: u32,
+}
+
+pub struct RateChart {
+    pub png: Vec<u8>,
+    pub caption: String,
+}
+
+fn parse_period(token: &str) -> Option<u32> {
+    let cap = PERIOD_RE.captures(token)?;
+    let amount: u32 = cap[1].parse().ok()?;
+    let multiplier = match cap[2].to_lowercase().as_str() {
+        "w" | "н" => 7,
+        "m" | "м" => 30,
+        "y" | "г" => 365,
+        _ => 1,
+    };
+    Some((amount * multiplier).clamp(1, MAX_CHART_DAYS))
+}
+
+// "usd uah 30d", "$ 7d", "eur" - the second currency defaults to UAH
+pub fn parse_chart_request(
+    converter: &CurrencyConverter,
+    aliases: &CurrencyAliases,
+    args: &str,
+) -> Option<ChartRequest> {
+    let mut days = 30;
+    let mut currencies = Vec::new();
+
+    for token in args.split_whitespace() {
+        if let Some(period) = parse_period(token) {
+            days = period;
+        } else {
+            currencies.push(converter.find_currency_info_by_identifier(token, aliases)?);
+        }
+    }
+
+    let mut currencies = currencies.into_iter();
+    let from = currencies.next()?;
+    let to = match currencies.next() {
+        Some(to) => to,
+        None => {
+            let fallback = if from.code == "UAH" { "USD" } else { "UAH" };
+            converter.find_currency_info_by_identifier(fallback, aliases)?
+        }
+    };
+
+    Some(ChartRequest { from, to, days })
+}
+
+pub fn format_rate(rate: f64) -> String {
+    if rate.abs() >= 1.0 {
+        format!("{:.4}", rate)
+    } else {
+        format!("{:.8}", rate)
+    }
+}
+
+// None when there are not enough snapshots yet
+pub async fn build_rate_chart(request: &ChartRequest) -> Result<Option<RateChart>, MyError> {
+    let since = Utc::now()
+        .date_naive()
+        .checked_sub_days(Days::new(request.days as u64))
+        .unwrap_or_default();
+
+    let sampled: Vec<(i64, f64)> =
+        RateSnapshot::pair_since(since, &request.from.code, &request.to.code, MAX_CHART_POINTS)
+            .await?
+            .into_iter()
+            .filter(|point| point.to != 0.0)
+            .map(|point| (point.fetched_at, point.from / point.to))
+            .collect();
+
+    if sampled.len() < 2 {
+        return Ok(None);
+    }
+
+    let (first, last) = (sampled[0], sampled[sampled.len() - 1]);
+    let min = sampled.iter().copied().min_by(|a, b| a.1.total_cmp(&b.1)).unwrap_or(first);
+    let max = sampled.iter().copied().max_by(|a, b| a.1.total_cmp(&b.1)).unwrap_or(first);
+
+    let date_format = if request.days <= 2 { "%d.%m %H:%M" } else { "%d.%m.%Y" };
+    let format_date = |timestamp: i64, format: &str| {
+        DateTime::from_timestamp(timestamp, 0)
+            .map(|date| date.format(format).to_string())
+            .unwrap_or_default()
+    };
+
+    let chart_points: Vec<ChartPoint> = sampled
+        .iter()
+        .map(|(timestamp, rate)| ChartPoint {
+            x: *timestamp as f64,
+            y: *rate,
+        })
+        .collect();
+    let tick_format = if request.days <= 2 { "%H:%M" } else { "%d.%m" };
+    let png = render_line_chart(
+        &chart_points,
+        800,
+        400,
+        |x| format_date(x as i64, tick_format),
+        format_rate,
+    )
+    .map_err(|e| MyError::Other(format!("Failed to encode chart: {}", e)))?;
+
+    let change = last.1 - first.1;
+    let change_percent = if first.1 != 0.0 { change / first.1 * 100.0 } else { 0.0 };
+
+    let caption = format!(
+        "{} {} → {} {} · {} дн.\n\n\
+        Сейчас: <b>{}</b>\n\
+        🔻 Мин: {} ({})\n\
+        🔺 Макс: {} ({})\n\
+        {} Изменение: {}{} ({:+.2}%)",
+        request.from.flag,
+        request.from.code,
+        request.to.flag,
+        request.to.code,
+        request.days,
+        format_rate(last.1),
+        format_rate(min.1),
+        format_date(min.0, date_format),
+        format_rate(max.1),
+        format_date(max.0, date_format),
+        if change >= 0.0 { "📈" } else { "📉" },
+        if change >= 0.0 { "+" } else { "" },
+        format_rate(change),
+        change_percent
+    );
+
+    Ok(Some(RateChart { png, caption }))
+}
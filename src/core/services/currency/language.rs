@@ -0,0 +1,145 @@
+use crate::core::services::currency::converter::CurrencyStruct;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputLanguage {
+    #[default]
+    Russian,
+    English,
+}
+
+pub const OUTPUT_LANGUAGES: &[OutputLanguage] = &[OutputLanguage::Russian, OutputLanguage::English];
+
+enum PluralForm {
+    One,
+    Few,
+    Many,
+}
+
+impl OutputLanguage {
+    pub fn code(&self) -> &'static str {
+        match self {
+            OutputLanguage::Russian => "russian",
+            OutputLanguage::English => "english",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        OUTPUT_LANGUAGES.iter().copied().find(|lang| lang.code() == code)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OutputLanguage::Russian => "🇷🇺 Русский",
+            OutputLanguage::English => "🇬🇧 English",
+        }
+    }
+
+    fn plural_form(&self, amount: f64) -> PluralForm {
+        match self {
+            // fractions always take the genitive singular: 1,5 доллара, 2,25 гривны
+            OutputLanguage::Russian if amount.fract() != 0.0 => PluralForm::Few,
+            OutputLanguage::Russian => {
+                let number = amount.abs().trunc() as u64;
+                let last_two_digits = number % 100;
+                let last_digit = number % 10;
+                if (11..=19).contains(&last_two_digits) {
+                    PluralForm::Many
+                } else if last_digit == 1 {
+                    PluralForm::One
+                } else if (2..=4).contains(&last_digit) {
+                    PluralForm::Few
+                } else {
+                    PluralForm::Many
+                }
+            }
+            OutputLanguage::English if amount == 1.0 => PluralForm::One,
+            OutputLanguage::English => PluralForm::Many,
+        }
+    }
+
+    // `decimals` has to match the displayed number, 1.00001 shown as "1,00" is still "1 доллар"
+    pub fn currency_word<'a>(&self, amount: f64, decimals: i32, info: &'a CurrencyStruct) -> &'a str {
+        let scale = 10f64.powi(decimals);
+        let amount = (amount * scale).round() / scale;
+        match (self, self.plural_form(amount)) {
+            (OutputLanguage::Russian, PluralForm::One) => &info.one,
+            (OutputLanguage::Russian, PluralForm::Few) => &info.few,
+            (OutputLanguage::Russian, PluralForm::Many) => &info.many,
+            (OutputLanguage::English, PluralForm::One) => &info.one_en,
+            (OutputLanguage::English, _) => &info.many_en,
+        }
+    }
+
+    // 1 234 567,89 vs 1,234,567.89
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        let (group_separator, decimal_separator) = match self {
+            OutputLanguage::Russian => ('\u{a0}', ','),
+            OutputLanguage::English => (',', '.'),
+        };
+
+        let formatted = format!("{:.*}", decimals, value.abs());
+        let (int_part, frac_part) = formatted.split_once('.').unwrap_or((&formatted, ""));
+
+        let mut result = String::new();
+        if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            result.push('-');
+        }
+        for (i, digit) in int_part.chars().enumerate() {
+            if i > 0 && (int_part.len() - i) % 3 == 0 {
+                result.push(group_separator);
+            }
+            result.push(digit);
+        }
+        if !frac_part.is_empty() {
+            result.push(decimal_separator);
+            result.push_str(frac_part);
+        }
+        result
+    }
+
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        match self {
+            OutputLanguage::Russian => date.format("%d.%m.%Y").to_string(),
+            OutputLanguage::English => date.format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    pub fn rate_on(&self, date: NaiveDate) -> String {
+        match self {
+            OutputLanguage::Russian => format!("курс на {}", self.format_date(date)),
+            OutputLanguage::English => format!("rate on {}", self.format_date(date)),
+        }
+    }
+
+    pub fn no_rates_on(&self, date: NaiveDate) -> String {
+        match self {
+            OutputLanguage::Russian => {
+                format!("📅 Нет сохранённых курсов на {}.", self.format_date(date))
+            }
+            OutputLanguage::English => {
+                format!("📅 No saved rates for {}.", self.format_date(date))
+            }
+        }
+    }
+
+    pub fn no_rate(&self) -> &'static str {
+        match self {
+            OutputLanguage::Russian => "нет курса",
+            OutputLanguage::English => "no rate",
+        }
+    }
+
+    pub fn future_date(&self, date: NaiveDate) -> String {
+        match self {
+            OutputLanguage::Russian => {
+                format!("📅 {} ещё не наступило, курсов на эту дату нет.", self.format_date(date))
+            }
+            OutputLanguage::English => {
+                format!("📅 {} hasn't come yet, there are no rates for it.", self.format_date(date))
+            }
+        }
+    }
+}
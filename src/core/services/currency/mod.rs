@@ -1 +1,6 @@
+pub mod catalogue;
 pub mod converter;
+pub mod expression;
+pub mod history;
+pub mod language;
+pub mod providers;
@@ -0,0 +1,61 @@
+use crate::core::services::currency::{
+    converter::ConvertError,
+    providers::{RateProvider, RateTable},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const COINBASE_API_URL: &str = "https://api.coinbase.com/v2/exchange-rates?currency=UAH";
+
+#[derive(Deserialize, Debug)]
+struct CoinbaseResponse {
+    data: CoinbaseData,
+}
+#[derive(Deserialize, Debug)]
+struct CoinbaseData {
+    currency: String,
+    rates: HashMap<String, String>,
+}
+
+pub struct CoinbaseProvider {
+    client: Client,
+}
+
+impl CoinbaseProvider {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl RateProvider for CoinbaseProvider {
+    fn name(&self) -> &'static str {
+        "coinbase"
+    }
+
+    async fn fetch(&self) -> Result<RateTable, ConvertError> {
+        let response = self.client.get(COINBASE_API_URL).send().await?;
+        let parsed = response.json::<CoinbaseResponse>().await?;
+
+        // coinbase answers "how much X for one UAH", we need it the other way around
+        let rates = parsed
+            .data
+            .rates
+            .into_iter()
+            .filter_map(|(currency_code, rate_str)| {
+                rate_str.parse::<f64>().ok().and_then(|rate_val| {
+                    if rate_val != 0.0 {
+                        Some((currency_code, 1.0 / rate_val))
+                    } else {
+                        None
+                    }
+                })
+            })
+            .collect();
+
+        Ok(RateTable::new(&parsed.data.currency, rates, Some(Utc::now())))
+    }
+}
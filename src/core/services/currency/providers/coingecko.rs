@@ -0,0 +1,61 @@
+use crate::core::services::currency::{
+    converter::{ConvertError, CurrencyStruct},
+    providers::{RateProvider, RateTable},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::Client;
+use std::collections::HashMap;
+
+const COINGECKO_API_URL: &str = "https://api.coingecko.com/api/v3/simple/price";
+
+pub struct CoinGeckoProvider {
+    client: Client,
+    id_to_code: HashMap<String, String>, // <"the-open-network", "TON">
+}
+
+impl CoinGeckoProvider {
+    pub fn new(client: Client, currencies: &[CurrencyStruct]) -> Self {
+        let id_to_code = currencies
+            .iter()
+            .filter_map(|currency| Some((currency.coingecko_id.clone()?, currency.code.clone())))
+            .collect();
+
+        Self { client, id_to_code }
+    }
+}
+
+#[async_trait]
+impl RateProvider for CoinGeckoProvider {
+    fn name(&self) -> &'static str {
+        "coingecko"
+    }
+
+    async fn fetch(&self) -> Result<RateTable, ConvertError> {
+        if self.id_to_code.is_empty() {
+            return Ok(RateTable::new("USD", HashMap::new(), None));
+        }
+
+        let ids = self.id_to_code.keys().cloned().collect::<Vec<_>>().join(",");
+        let response = self
+            .client
+            .get(COINGECKO_API_URL)
+            .query(&[("ids", ids.as_str()), ("vs_currencies", "usd")])
+            .send()
+            .await?;
+
+        let parsed = response
+            .json::<HashMap<String, HashMap<String, f64>>>()
+            .await?;
+
+        let rates = parsed
+            .into_iter()
+            .filter_map(|(id, prices)| {
+                let code = self.id_to_code.get(&id)?;
+                Some((code.clone(), *prices.get("usd")?))
+            })
+            .collect();
+
+        Ok(RateTable::new("USD", rates, Some(Utc::now())))
+    }
+}
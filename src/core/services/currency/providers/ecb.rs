@@ -0,0 +1,62 @@
+use crate::core::services::currency::{
+    converter::ConvertError,
+    providers::{RateProvider, RateTable},
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::Client;
+use std::collections::HashMap;
+
+// no UAH and no RUB in this feed, so it is only good as the last fiat fallback
+const ECB_API_URL: &str = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml";
+
+// the feed is a flat list of <Cube> tags, not worth pulling in an xml parser
+static ECB_TIME_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"time=['"](\d{4}-\d{2}-\d{2})['"]"#).unwrap());
+static ECB_RATE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"currency=['"]([A-Z]{3})['"]\s+rate=['"]([\d.]+)['"]"#).unwrap()
+});
+
+pub struct EcbProvider {
+    client: Client,
+}
+
+impl EcbProvider {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl RateProvider for EcbProvider {
+    fn name(&self) -> &'static str {
+        "ecb"
+    }
+
+    async fn fetch(&self) -> Result<RateTable, ConvertError> {
+        let body = self.client.get(ECB_API_URL).send().await?.text().await?;
+
+        let updated_at = ECB_TIME_RE
+            .captures(&body)
+            .and_then(|cap| NaiveDate::parse_from_str(&cap[1], "%Y-%m-%d").ok())
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|date| date.and_utc());
+
+        // the feed says "how much X for one EUR"
+        let rates: HashMap<String, f64> = ECB_RATE_RE
+            .captures_iter(&body)
+            .filter_map(|cap| {
+                let rate = cap[2].parse::<f64>().ok().filter(|rate| *rate != 0.0)?;
+                Some((cap[1].to_string(), 1.0 / rate))
+            })
+            .collect();
+
+        if rates.is_empty() {
+            return Err(ConvertError::ApiError("ECB feed contained no rates".to_string()));
+        }
+
+        Ok(RateTable::new("EUR", rates, updated_at))
+    }
+}
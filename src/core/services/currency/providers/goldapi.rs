@@ -0,0 +1,79 @@
+use crate::core::services::currency::{
+    converter::{ConvertError, CurrencyStruct},
+    providers::{RateProvider, RateTable},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::future::join_all;
+use log::warn;
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const GOLD_API_URL: &str = "https://api.gold-api.com/price";
+
+#[derive(Deserialize, Debug)]
+struct GoldApiResponse {
+    price: f64,
+}
+
+// metals only, price of one troy ounce in USD, used when stooq is down
+pub struct GoldApiProvider {
+    client: Client,
+    codes: Vec<String>, // "XAU", "XAG"
+}
+
+impl GoldApiProvider {
+    pub fn new(client: Client, currencies: &[CurrencyStruct]) -> Self {
+        let codes = currencies
+            .iter()
+            .filter(|currency| currency.source == "metal")
+            .map(|currency| currency.code.clone())
+            .collect();
+
+        Self { client, codes }
+    }
+
+    async fn fetch_price(&self, code: &str) -> Result<f64, ConvertError> {
+        let response = self
+            .client
+            .get(format!("{}/{}", GOLD_API_URL, code))
+            .send()
+            .await?
+            .json::<GoldApiResponse>()
+            .await?;
+        Ok(response.price)
+    }
+}
+
+#[async_trait]
+impl RateProvider for GoldApiProvider {
+    fn name(&self) -> &'static str {
+        "goldapi"
+    }
+
+    async fn fetch(&self) -> Result<RateTable, ConvertError> {
+        if self.codes.is_empty() {
+            return Ok(RateTable::new("USD", HashMap::new(), None));
+        }
+
+        let prices = join_all(self.codes.iter().map(|code| self.fetch_price(code))).await;
+
+        let mut rates = HashMap::new();
+        for (code, price) in self.codes.iter().zip(prices) {
+            match price {
+                Ok(price) if price > 0.0 => {
+                    rates.insert(code.clone(), price);
+                }
+                Ok(_) => warn!("gold-api returned a zero price for {}", code),
+                Err(e) => warn!("gold-api failed for {}: {}", code, e),
+            }
+        }
+
+        if rates.is_empty() {
+            return Err(ConvertError::ApiError("gold-api returned no prices".to_string()));
+        }
+
+        Ok(RateTable::new("USD", rates, Some(Utc::now())))
+    }
+}
@@ -0,0 +1,236 @@
+pub mod coinbase;
+pub mod coingecko;
+pub mod ecb;
+pub mod goldapi;
+pub mod nbu;
+pub mod static_file;
+pub mod stooq;
+pub mod tonapi;
+
+use crate::core::{
+    config::json::{RateProviderKind, RatesConfig},
+    services::currency::{
+        converter::{ConvertError, CurrencyStruct},
+        providers::{
+            coinbase::CoinbaseProvider, coingecko::CoinGeckoProvider, ecb::EcbProvider,
+            goldapi::GoldApiProvider, nbu::NbuProvider, static_file::StaticFileProvider,
+            stooq::StooqProvider, tonapi::TonApiProvider,
+        },
+    },
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use log::warn;
+use reqwest::Client;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+// rates[code] is the price of one `code` in `base_code`, the base itself is always 1.0
+#[derive(Debug, Clone)]
+pub struct RateTable {
+    pub base_code: String,
+    pub rates: HashMap<String, f64>,
+    pub updated_at: Option<DateTime<Utc>>, // None = never goes stale
+}
+
+impl RateTable {
+    pub fn new(base_code: &str, mut rates: HashMap<String, f64>, updated_at: Option<DateTime<Utc>>) -> Self {
+        rates.insert(base_code.to_string(), 1.0);
+        Self {
+            base_code: base_code.to_string(),
+            rates,
+            updated_at,
+        }
+    }
+
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.updated_at.is_some_and(|updated_at| {
+            (Utc::now() - updated_at).to_std().unwrap_or_default() > max_age
+        })
+    }
+
+    // rebases `other` onto our base through any currency both tables know, existing rates win
+    pub fn merge(&mut self, other: RateTable) -> bool {
+        let anchor = std::iter::once(&other.base_code)
+            .chain(other.rates.keys())
+            .find_map(|code| Some(self.rates.get(code)? / other.rates.get(code)?));
+
+        let Some(factor) = anchor else {
+            return false;
+        };
+
+        for (code, rate) in other.rates {
+            self.rates.entry(code).or_insert(rate * factor);
+        }
+        true
+    }
+}
+
+#[async_trait]
+pub trait RateProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn fetch(&self) -> Result<RateTable, ConvertError>;
+}
+
+fn build_rate_provider(
+    kind: &RateProviderKind,
+    config: &RatesConfig,
+    currencies: &[CurrencyStruct],
+    client: &Client,
+) -> Option<Arc<dyn RateProvider>> {
+    match kind {
+        RateProviderKind::Coinbase => Some(Arc::new(CoinbaseProvider::new(client.clone()))),
+        RateProviderKind::Nbu => Some(Arc::new(NbuProvider::new(client.clone()))),
+        RateProviderKind::Ecb => Some(Arc::new(EcbProvider::new(client.clone()))),
+        RateProviderKind::TonApi => Some(Arc::new(TonApiProvider::new(client.clone(), currencies))),
+        RateProviderKind::CoinGecko => {
+            Some(Arc::new(CoinGeckoProvider::new(client.clone(), currencies)))
+        }
+        RateProviderKind::Stooq => Some(Arc::new(StooqProvider::new(client.clone(), currencies))),
+        RateProviderKind::GoldApi => {
+            Some(Arc::new(GoldApiProvider::new(client.clone(), currencies)))
+        }
+        RateProviderKind::Static => match &config.static_file {
+            Some(path) => Some(Arc::new(StaticFileProvider::new(path.clone()))),
+            None => {
+                warn!("Static rate provider is listed, but `static_file` is not set, skipping it");
+                None
+            }
+        },
+    }
+}
+
+pub fn build_rate_providers(
+    kinds: &[RateProviderKind],
+    config: &RatesConfig,
+    currencies: &[CurrencyStruct],
+    client: &Client,
+) -> Vec<Arc<dyn RateProvider>> {
+    kinds
+        .iter()
+        .filter_map(|kind| build_rate_provider(kind, config, currencies, client))
+        .collect()
+}
+
+// the first provider that answers with fresh data wins
+pub async fn fetch_first_fresh(
+    providers: &[Arc<dyn RateProvider>],
+    max_age: Duration,
+) -> Option<RateTable> {
+    for provider in providers {
+        match provider.fetch().await {
+            Ok(table) if table.is_stale(max_age) => {
+                warn!("Rates from {} are stale, trying the next provider", provider.name());
+            }
+            Ok(table) => return Some(table),
+            Err(e) => warn!("Rate provider {} failed: {}", provider.name(), e),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(base: &str, rates: &[(&str, f64)]) -> RateTable {
+        let rates = rates.iter().map(|(code, rate)| (code.to_string(), *rate)).collect();
+        RateTable::new(base, rates, Some(Utc::now()))
+    }
+
+    struct FakeProvider {
+        name: &'static str,
+        result: Option<RateTable>,
+    }
+
+    #[async_trait]
+    impl RateProvider for FakeProvider {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn fetch(&self) -> Result<RateTable, ConvertError> {
+            self.result
+                .clone()
+                .ok_or_else(|| ConvertError::ApiError(format!("{} is down", self.name)))
+        }
+    }
+
+    fn provider(name: &'static str, result: Option<RateTable>) -> Arc<dyn RateProvider> {
+        Arc::new(FakeProvider { name, result })
+    }
+
+    #[test]
+    fn merge_rebases_through_a_shared_currency() {
+        // 1 usd = 0.9 eur, 1 ton = 3 usd
+        let mut fiat = table("EUR", &[("USD", 0.9)]);
+        let tokens = table("USD", &[("TON", 3.0)]);
+
+        assert!(fiat.merge(tokens));
+        assert!((fiat.rates["TON"] - 2.7).abs() < 1e-9);
+        assert_eq!(fiat.rates["EUR"], 1.0);
+    }
+
+    #[test]
+    fn merge_keeps_existing_rates() {
+        let mut fiat = table("UAH", &[("USD", 41.0)]);
+        let other = table("USD", &[("UAH", 0.5)]);
+
+        assert!(fiat.merge(other));
+        assert_eq!(fiat.rates["UAH"], 1.0);
+        assert_eq!(fiat.rates["USD"], 41.0);
+    }
+
+    #[test]
+    fn merge_without_a_shared_currency_changes_nothing() {
+        let mut fiat = table("EUR", &[("GBP", 1.2)]);
+        let tokens = table("UAH", &[("TON", 120.0)]);
+
+        assert!(!fiat.merge(tokens));
+        assert!(!fiat.rates.contains_key("TON"));
+    }
+
+    #[tokio::test]
+    async fn fetch_first_fresh_skips_failed_and_stale_providers() {
+        let stale = RateTable::new(
+            "UAH",
+            HashMap::from([("USD".to_string(), 40.0)]),
+            Some(Utc::now() - chrono::Duration::days(10)),
+        );
+        let providers = [
+            provider("down", None),
+            provider("stale", Some(stale)),
+            provider("fresh", Some(table("EUR", &[("USD", 0.9)]))),
+            provider("unused", Some(table("USD", &[]))),
+        ];
+
+        let table = fetch_first_fresh(&providers, Duration::from_secs(72 * 60 * 60)).await;
+        assert_eq!(table.map(|table| table.base_code), Some("EUR".to_string()));
+    }
+
+    #[tokio::test]
+    async fn fetch_first_fresh_gives_up_when_nobody_answers() {
+        let providers = [provider("down", None), provider("also down", None)];
+        assert!(fetch_first_fresh(&providers, Duration::from_secs(60)).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn static_file_never_goes_stale_without_a_timestamp() {
+        let path = std::env::temp_dir().join(format!("rates-{}.json", uuid::Uuid::new_v4()));
+        tokio::fs::write(&path, r#"{"base_code": "UAH", "rates": {"USD": 41.5}}"#)
+            .await
+            .unwrap();
+
+        let providers = [
+            provider("down", None),
+            Arc::new(StaticFileProvider::new(path.to_string_lossy().into_owned())) as Arc<dyn RateProvider>,
+        ];
+        let table = fetch_first_fresh(&providers, Duration::from_secs(60)).await;
+        let _ = tokio::fs::remove_file(&path).await;
+
+        let table = table.unwrap();
+        assert_eq!(table.base_code, "UAH");
+        assert_eq!(table.rates["USD"], 41.5);
+        assert_eq!(table.rates["UAH"], 1.0);
+    }
+}
@@ -0,0 +1,53 @@
+use crate::core::services::currency::{
+    converter::ConvertError,
+    providers::{RateProvider, RateTable},
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use reqwest::Client;
+use serde::Deserialize;
+
+const NBU_API_URL: &str = "https://bank.gov.ua/NBUStatService/v1/statdirectory/exchange?json";
+
+#[derive(Deserialize, Debug)]
+struct NbuRate {
+    cc: String,
+    rate: f64, // uah for one unit
+    exchangedate: String, // dd.mm.yyyy
+}
+
+pub struct NbuProvider {
+    client: Client,
+}
+
+impl NbuProvider {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl RateProvider for NbuProvider {
+    fn name(&self) -> &'static str {
+        "nbu"
+    }
+
+    async fn fetch(&self) -> Result<RateTable, ConvertError> {
+        let response = self.client.get(NBU_API_URL).send().await?;
+        let parsed = response.json::<Vec<NbuRate>>().await?;
+
+        let updated_at = parsed
+            .first()
+            .and_then(|rate| NaiveDate::parse_from_str(&rate.exchangedate, "%d.%m.%Y").ok())
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|date| date.and_utc());
+
+        let rates = parsed
+            .into_iter()
+            .filter(|rate| rate.rate > 0.0)
+            .map(|rate| (rate.cc, rate.rate))
+            .collect();
+
+        Ok(RateTable::new("UAH", rates, updated_at))
+    }
+}
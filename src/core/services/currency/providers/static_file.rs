@@ -0,0 +1,48 @@
+use crate::core::services::currency::{
+    converter::ConvertError,
+    providers::{RateProvider, RateTable},
+};
+use async_trait::async_trait;
+use chrono::DateTime;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// {"base_code": "UAH", "rates": {"USD": 41.5}, "updated_at": 1700000000}
+#[derive(Deserialize, Debug)]
+struct StaticRates {
+    base_code: String,
+    rates: HashMap<String, f64>,
+    #[serde(default)]
+    updated_at: Option<i64>,
+}
+
+pub struct StaticFileProvider {
+    path: String,
+}
+
+impl StaticFileProvider {
+    pub fn new(path: String) -> Self {
+        Self { path }
+    }
+}
+
+#[async_trait]
+impl RateProvider for StaticFileProvider {
+    fn name(&self) -> &'static str {
+        "static"
+    }
+
+    async fn fetch(&self) -> Result<RateTable, ConvertError> {
+        let content = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(|e| ConvertError::ConfigFileReadError(self.path.clone(), e))?;
+        let parsed: StaticRates = serde_json::from_str(&content)
+            .map_err(|e| ConvertError::ConfigFileParseError(self.path.clone(), e))?;
+
+        let updated_at = parsed
+            .updated_at
+            .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0));
+
+        Ok(RateTable::new(&parsed.base_code, parsed.rates, updated_at))
+    }
+}
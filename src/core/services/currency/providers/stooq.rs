@@ -0,0 +1,76 @@
+use crate::core::services::currency::{
+    converter::{ConvertError, CurrencyStruct},
+    providers::{RateProvider, RateTable},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::Client;
+use std::collections::HashMap;
+
+const STOOQ_API_URL: &str = "https://stooq.com/q/l/";
+
+pub const MARKET_SOURCES: [&str; 2] = ["metal", "stock"];
+
+// quotes for metals ("xauusd") and stocks ("aapl.us"), everything is priced in USD
+pub struct StooqProvider {
+    client: Client,
+    symbol_to_code: HashMap<String, String>, // <"aapl.us", "AAPL">
+}
+
+impl StooqProvider {
+    pub fn new(client: Client, currencies: &[CurrencyStruct]) -> Self {
+        let symbol_to_code = currencies
+            .iter()
+            .filter(|currency| MARKET_SOURCES.contains(&currency.source.as_str()))
+            .filter_map(|currency| {
+                Some((currency.api_identifier.clone()?.to_lowercase(), currency.code.clone()))
+            })
+            .collect();
+
+        Self {
+            client,
+            symbol_to_code,
+        }
+    }
+}
+
+#[async_trait]
+impl RateProvider for StooqProvider {
+    fn name(&self) -> &'static str {
+        "stooq"
+    }
+
+    async fn fetch(&self) -> Result<RateTable, ConvertError> {
+        if self.symbol_to_code.is_empty() {
+            return Ok(RateTable::new("USD", HashMap::new(), None));
+        }
+
+        let symbols = self.symbol_to_code.keys().cloned().collect::<Vec<_>>().join(" ");
+        let body = self
+            .client
+            .get(STOOQ_API_URL)
+            .query(&[("s", symbols.as_str()), ("f", "sc"), ("e", "csv")])
+            .send()
+            .await?
+            .text()
+            .await?;
+
+        // "AAPL.US,189.84" per line, unknown symbols come back as "N/D"
+        let rates: HashMap<String, f64> = body
+            .lines()
+            .filter_map(|line| {
+                let (symbol, close) = line.trim().split_once(',')?;
+                let code = self.symbol_to_code.get(&symbol.to_lowercase())?;
+                let price = close.parse::<f64>().ok().filter(|price| *price > 0.0)?;
+                Some((code.clone(), price))
+            })
+            .collect();
+
+        if rates.is_empty() {
+            return Err(ConvertError::ApiError("Stooq returned no quotes".to_string()));
+        }
+
+        // a closed market keeps its last close, which is still the price to convert with
+        Ok(RateTable::new("USD", rates, Some(Utc::now())))
+    }
+}
@@ -0,0 +1,121 @@
+use crate::core::services::currency::{
+    converter::{ConvertError, CurrencyStruct},
+    providers::{RateProvider, RateTable},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use log::debug;
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const TONAPI_URL: &str = "https://tonapi.io/v2/rates";
+
+#[derive(Deserialize, Debug)]
+struct TonApiResponse {
+    rates: HashMap<String, TonRateEntry>,
+}
+#[derive(Deserialize, Debug, Clone)]
+struct TonRateEntry {
+    prices: HashMap<String, f64>,
+}
+
+pub struct TonApiProvider {
+    client: Client,
+
+    // for fucking ton api
+    ton_tickers: Vec<String>,
+    ton_addresses: Vec<String>,
+    ton_ticker_to_code: HashMap<String, String>, // <"ton", "TON">
+    ton_address_to_code: HashMap<String, String>, // <"EQ..NOT", "NOT">
+}
+
+impl TonApiProvider {
+    pub fn new(client: Client, currencies: &[CurrencyStruct]) -> Self {
+        let mut ton_tickers = Vec::new();
+        let mut ton_addresses = Vec::new();
+        let mut ton_ticker_to_code = HashMap::new();
+        let mut ton_address_to_code = HashMap::new();
+
+        for currency in currencies {
+            if currency.source == "tonapi"
+                && let Some(identifier) = &currency.api_identifier
+            {
+                if identifier.len() > 10
+                    && (identifier.starts_with("EQ") || identifier.starts_with("UQ"))
+                {
+                    ton_addresses.push(identifier.clone());
+                    ton_address_to_code.insert(identifier.clone(), currency.code.clone());
+                } else {
+                    let lower_ticker = identifier.to_lowercase();
+                    ton_tickers.push(lower_ticker.clone());
+                    ton_ticker_to_code.insert(lower_ticker, currency.code.clone());
+                }
+            }
+        }
+
+        Self {
+            client,
+            ton_tickers,
+            ton_addresses,
+            ton_ticker_to_code,
+            ton_address_to_code,
+        }
+    }
+}
+
+#[async_trait]
+impl RateProvider for TonApiProvider {
+    fn name(&self) -> &'static str {
+        "tonapi"
+    }
+
+    async fn fetch(&self) -> Result<RateTable, ConvertError> {
+        let all_tokens = [self.ton_tickers.as_slice(), self.ton_addresses.as_slice()].concat();
+        if all_tokens.is_empty() {
+            return Ok(RateTable::new("USD", HashMap::new(), None));
+        }
+
+        let tokens_str = all_tokens.join(",");
+        let response = self
+            .client
+            .get(TONAPI_URL)
+            .query(&[("tokens", &tokens_str), ("currencies", &"usd".to_string())])
+            .send()
+            .await?;
+
+        let parsed = response.json::<TonApiResponse>().await?;
+
+        let mut crypto_rates = HashMap::new();
+
+        parsed
+            .rates
+            .iter()
+            .for_each(|(api_identifier, rate_entry)| {
+                let mut code: Option<String> = None;
+
+                if let Some(found_code) = self.ton_address_to_code.get(api_identifier) {
+                    code = Some(found_code.clone());
+                } else if let Some(found_code) =
+                    self.ton_ticker_to_code.get(&api_identifier.to_lowercase())
+                {
+                    code = Some(found_code.clone());
+                }
+
+                if let Some(found_code) = code {
+                    if let Some(price_in_usd) = rate_entry.prices.get("USD") {
+                        crypto_rates.insert(found_code, *price_in_usd);
+                    }
+                } else {
+                    // ???
+                    debug!(
+                        "Skipped unknown API identifier from TonAPI: {}",
+                        api_identifier
+                    );
+                }
+            });
+
+        // usd is in every fiat table, uah is missing from the ecb one
+        Ok(RateTable::new("USD", crypto_rates, Some(Utc::now())))
+    }
+}
@@ -0,0 +1,189 @@
+use crate::errors::MyError;
+use bytes::Bytes;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+// ffmpeg can't seek in piped mp4, so the media is written to a temp file first
+pub struct MediaFile {
+    path: PathBuf,
+}
+
+// what ffprobe says about a file, enough to tell whether telegram will play it
+pub struct MediaInfo {
+    pub format_name: String,
+    pub codecs: Vec<String>,
+}
+
+impl MediaInfo {
+    // h264 with aac/mp3 in mp4 plays inline everywhere, the rest may end up as a document
+    fn has_telegram_codecs(&self) -> bool {
+        self.codecs.iter().any(|codec| codec == "h264")
+            && self
+                .codecs
+                .iter()
+                .all(|codec| matches!(codec.as_str(), "h264" | "aac" | "mp3"))
+    }
+
+    fn is_mp4(&self) -> bool {
+        self.format_name.split(',').any(|format| format == "mp4")
+    }
+}
+
+impl MediaFile {
+    // the file itself is created by whoever writes into `path()`
+    pub fn temp() -> Self {
+        Self {
+            path: std::env::temp_dir().join(format!("fulturate-{}", uuid::Uuid::new_v4())),
+        }
+    }
+
+    pub async fn from_bytes(data: &Bytes) -> Result<Self, MyError> {
+        let file = Self::temp();
+        tokio::fs::write(&file.path, data).await?;
+        Ok(file)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub async fn size(&self) -> Result<u64, MyError> {
+        Ok(tokio::fs::metadata(&self.path).await?.len())
+    }
+
+    pub async fn probe(&self) -> Result<MediaInfo, MyError> {
+        let output = Command::new("ffprobe")
+            .args(["-v", "error", "-show_entries", "format=format_name:stream=codec_name"])
+            .args(["-of", "default=noprint_wrappers=1"])
+            .arg(&self.path)
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(MyError::Other(format!(
+                "ffprobe failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        let mut info = MediaInfo {
+            format_name: String::new(),
+            codecs: Vec::new(),
+        };
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            match line.split_once('=') {
+                Some(("format_name", value)) => info.format_name = value.to_string(),
+                Some(("codec_name", value)) => info.codecs.push(value.to_string()),
+                _ => {}
+            }
+        }
+        Ok(info)
+    }
+
+    // None when the file is fine as it is, otherwise a remuxed or re-encoded copy
+    pub async fn to_telegram_mp4(&self) -> Result<Option<MediaFile>, MyError> {
+        let info = self.probe().await?;
+        let copy_streams = info.has_telegram_codecs();
+        if copy_streams && info.is_mp4() {
+            return Ok(None);
+        }
+
+        let output_file = Self::temp();
+        let mut command = Command::new("ffmpeg");
+        command.args(["-v", "error", "-i"]).arg(&self.path);
+        if copy_streams {
+            command.args(["-c", "copy"]);
+        } else {
+            command
+                .args(["-c:v", "libx264", "-preset", "veryfast", "-crf", "23"])
+                .args(["-pix_fmt", "yuv420p", "-c:a", "aac", "-b:a", "128k"]);
+        }
+
+        let output = command
+            .args(["-movflags", "+faststart", "-f", "mp4"])
+            .arg(&output_file.path)
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(MyError::Other(format!(
+                "ffmpeg failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        Ok(Some(output_file))
+    }
+
+    pub async fn duration(&self) -> Result<f64, MyError> {
+        let output = Command::new("ffprobe")
+            .args(["-v", "error", "-show_entries", "format=duration"])
+            .args(["-of", "default=noprint_wrappers=1:nokey=1"])
+            .arg(&self.path)
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(MyError::Other(format!(
+                "ffprobe failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse::<f64>()
+            .map_err(|e| MyError::Other(format!("ffprobe returned invalid duration: {}", e)))
+    }
+
+    // mono 16k opus is plenty for speech and keeps chunks small
+    pub async fn cut_audio(&self, start: f64, length: f64) -> Result<Bytes, MyError> {
+        let output = Command::new("ffmpeg")
+            .args(["-v", "error", "-ss", &format!("{:.3}", start), "-t", &format!("{:.3}", length)])
+            .arg("-i")
+            .arg(&self.path)
+            .args(["-vn", "-ac", "1", "-ar", "16000", "-c:a", "libopus", "-b:a", "32k"])
+            .args(["-f", "ogg", "pipe:1"])
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(MyError::Other(format!(
+                "ffmpeg failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        Ok(Bytes::from(output.stdout))
+    }
+
+    // re-encodes the whole file through the given audio filter chain, video is dropped
+    pub async fn filter_audio(&self, filters: &[String]) -> Result<Bytes, MyError> {
+        let mut command = Command::new("ffmpeg");
+        command.args(["-v", "error", "-i"]).arg(&self.path).arg("-vn");
+        if !filters.is_empty() {
+            command.args(["-af", &filters.join(",")]);
+        }
+
+        let output = command
+            .args(["-ac", "1", "-ar", "16000", "-c:a", "libopus", "-b:a", "32k"])
+            .args(["-f", "ogg", "pipe:1"])
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(MyError::Other(format!(
+                "ffmpeg failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        Ok(Bytes::from(output.stdout))
+    }
+}
+
+impl Drop for MediaFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
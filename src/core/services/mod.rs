@@ -1,5 +1,11 @@
+pub mod alerts;
+pub mod chart;
 pub mod cobalt;
+pub mod cobalt_pool;
 pub mod currencier;
 pub mod currency;
+pub mod ffmpeg;
 pub mod speech_recognition;
-pub mod translation;
\ No newline at end of file
+pub mod transcription;
+pub mod translation;
+pub mod uploader;
\ No newline at end of file
@@ -1,34 +1,63 @@
 use crate::{
-    bot::keyboards::transcription::{
-        create_summary_keyboard, create_transcription_keyboard, create_retry_keyboard,
+    bot::{
+        commands::translate::TranslateJob,
+        keyboards::{
+            transcription::{
+                create_summary_keyboard, create_summary_styles_keyboard,
+                create_transcription_keyboard, create_retry_keyboard,
+            },
+            translate::create_language_keyboard,
+        },
+        modules::speech::{get_chat_speech_settings, get_user_speech_settings},
+    },
+    core::{
+        config::Config,
+        db::schemas::transcript::Transcript,
+        services::{
+            ffmpeg::MediaFile,
+            transcription::{
+                TranscriptSegment,
+                chunking::{Window, merge_segments, merge_texts, plan_windows},
+                diarization::{normalize_speakers, speaker_turns},
+                preprocess::preprocess_audio,
+                subtitles::SubtitleFormat,
+            },
+        },
     },
-    core::config::Config,
     errors::MyError,
-    util::{enums::AudioStruct, is_admin_or_author, split_text},
+    util::{enums::AudioStruct, is_admin_or_author, pack_paragraphs, split_text},
 };
 use bytes::Bytes;
-use gem_rs::{
-    api::Models,
-    client::GemSession,
-    types::{Blob, Context, HarmBlockThreshold, Role, Settings},
-};
-use log::{debug, error, info};
+use futures::{StreamExt, stream};
+use log::{debug, error, info, warn};
 use redis_macros::{FromRedisValue, ToRedisArgs};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::collections::HashMap;
 use teloxide::{
     prelude::*,
-    types::{FileId, MessageKind, ParseMode, ReplyParameters},
+    types::{FileId, InputFile, MessageId, MessageKind, ParseMode, ReplyParameters},
 };
 use teloxide::utils::html;
 
 #[derive(Debug, Serialize, Deserialize, FromRedisValue, ToRedisArgs, Clone)]
 pub struct TranscriptionCache {
     pub full_text: String,
-    pub summary: Option<String>,
+    #[serde(default)]
+    pub summaries: HashMap<String, String>, // style key -> summary
     pub file_id: String,
     pub mime_type: String,
     pub attempt: u32,
+    #[serde(default)]
+    pub segments: Option<Vec<TranscriptSegment>>,
+    #[serde(default)]
+    pub diarized: bool,
+}
+
+pub fn transcript_pages(entry: &TranscriptionCache) -> Vec<String> {
+    match &entry.segments {
+        Some(segments) if entry.diarized => pack_paragraphs(&speaker_turns(segments), 4000),
+        _ => split_text(&entry.full_text, 4000),
+    }
 }
 
 pub struct Transcription {
@@ -58,33 +87,23 @@ pub async fn pagination_handler(
         return Ok(());
     };
 
-    let cache = config.get_redis_client();
-    let message_cache_key = format!("message_file_map:{}", message.id);
-    let Some(file_unique_id): Option<String> = cache.get::<String>(&message_cache_key).await?
-    else {
-        bot.edit_message_text(message.chat.id, message.id, "❌ Кнопка устарела.")
-            .await?;
-        return Ok(());
-    };
-
-    let file_cache_key = format!("transcription_by_file:{}", file_unique_id);
-    let Some(cache_entry) = cache.get::<TranscriptionCache>(&file_cache_key).await? else {
-        bot.edit_message_text(
-            message.chat.id,
-            message.id,
-            "❌ Не удалось найти текст в кеше.",
-        )
+    let Some(cache_entry) = get_transcript_for_message(config, message.chat.id, message.id).await? else {
+        bot.answer_callback_query(query.id.clone())
+            .text("❌ Кнопка устарела.")
+            .show_alert(true)
             .await?;
         return Ok(());
     };
 
-    let text_parts = split_text(&cache_entry.full_text, 4000);
+    let text_parts = transcript_pages(&cache_entry);
     if page >= text_parts.len() {
         return Ok(());
     }
 
     let new_text = format!("<blockquote expandable>{}</blockquote>", text_parts[page]);
-    let new_keyboard = create_transcription_keyboard(page, text_parts.len(), query.from.id.0);
+    let allow_summaries = get_chat_speech_settings(&message.chat).await.allow_summaries;
+    let new_keyboard =
+        create_transcription_keyboard(page, text_parts.len(), query.from.id.0, allow_summaries);
 
     if message.text() != Some(new_text.as_str()) || message.reply_markup() != Some(&new_keyboard) {
         bot.edit_message_text(message.chat.id, message.id, new_text)
@@ -101,33 +120,18 @@ pub async fn back_handler(bot: Bot, query: CallbackQuery, config: &Config) -> Re
         return Ok(());
     };
 
-    let cache = config.get_redis_client();
-    let message_cache_key = format!("message_file_map:{}", message.id);
-    let Some(file_unique_id) = cache.get::<String>(&message_cache_key).await? else {
-        bot.edit_message_text(
-            message.chat.id,
-            message.id,
-            "❌ Не удалось найти исходное сообщение.",
-        )
-            .await?;
-        return Ok(());
-    };
-
-    let file_cache_key = format!("transcription_by_file:{}", file_unique_id);
-    let Some(cache_entry): Option<TranscriptionCache> =
-        cache.get::<TranscriptionCache>(&file_cache_key).await?
-    else {
-        bot.edit_message_text(
-            message.chat.id,
-            message.id,
-            "❌ Не удалось найти текст в кеше.",
-        )
+    let Some(cache_entry) = get_transcript_for_message(config, message.chat.id, message.id).await? else {
+        bot.answer_callback_query(query.id)
+            .text("❌ Кнопка устарела.")
+            .show_alert(true)
             .await?;
         return Ok(());
     };
 
-    let text_parts = split_text(&cache_entry.full_text, 4000);
-    let keyboard = create_transcription_keyboard(0, text_parts.len(), query.from.id.0);
+    let text_parts = transcript_pages(&cache_entry);
+    let allow_summaries = get_chat_speech_settings(&message.chat).await.allow_summaries;
+    let keyboard =
+        create_transcription_keyboard(0, text_parts.len(), query.from.id.0, allow_summaries);
 
     bot.edit_message_text(
         message.chat.id,
@@ -167,6 +171,87 @@ pub async fn summarization_handler(
         return Ok(());
     }
 
+    if !get_chat_speech_settings(&message.chat).await.allow_summaries {
+        bot.answer_callback_query(query.id)
+            .text("❌ Краткое содержание выключено в этом чате.")
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    }
+
+    let Some(cache_entry) = get_transcript_for_message(config, message.chat.id, message.id).await? else {
+        bot.answer_callback_query(query.id)
+            .text("❌ Кнопка устарела.")
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    };
+
+    if cache_entry.full_text.contains("[no speech]") {
+        bot.edit_message_text(message.chat.id, message.id, "❌ Нельзя составить краткое содержание из аудио без речи.")
+            .parse_mode(ParseMode::Html)
+            .reply_markup(create_summary_keyboard(user_id))
+            .await?;
+        return Ok(());
+    }
+
+    bot.answer_callback_query(query.id).await?;
+
+    let styles = config.get_json_config().get_summary_styles();
+    bot.edit_message_text(
+        message.chat.id,
+        message.id,
+        "✨ Выберите формат краткого содержания:",
+    )
+        .reply_markup(create_summary_styles_keyboard(styles, user_id))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn summary_style_handler(
+    bot: Bot,
+    query: CallbackQuery,
+    config: &Config,
+    style_key: &str,
+    user_id: u64,
+) -> Result<(), MyError> {
+    let Some(message) = query.message.and_then(|m| m.regular_message().cloned()) else {
+        return Ok(());
+    };
+
+    if !is_admin_or_author(
+        &bot,
+        message.chat.id,
+        message.chat.is_group() || message.chat.is_supergroup(),
+        &query.from,
+        user_id,
+    )
+        .await
+    {
+        bot.answer_callback_query(query.id)
+            .text("❌ У вас нет прав использовать эту кнопку!")
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    }
+
+    if !get_chat_speech_settings(&message.chat).await.allow_summaries {
+        bot.answer_callback_query(query.id)
+            .text("❌ Краткое содержание выключено в этом чате.")
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    }
+
+    let Some(style) = config.get_json_config().get_summary_style(style_key) else {
+        bot.answer_callback_query(query.id)
+            .text("❌ Такого формата больше нет.")
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    };
+
     let Some(audio_message_id) = message.reply_to_message().map(|m| m.id.0) else {
         bot.answer_callback_query(query.id)
             .text("❌ Не удалось найти исходное сообщение для повторной попытки.")
@@ -175,44 +260,30 @@ pub async fn summarization_handler(
         return Ok(());
     };
 
-    let cache = config.get_redis_client();
-    let message_file_map_key = format!("message_file_map:{}", message.id);
-    let Some(file_unique_id) = cache.get::<String>(&message_file_map_key).await? else {
-        bot.edit_message_text(message.chat.id, message.id, "❌ Кнопка устарела.")
+    let Some((file_unique_id, mut cache_entry)) =
+        load_transcript(config, message.chat.id, message.id).await?
+    else {
+        bot.answer_callback_query(query.id)
+            .text("❌ Кнопка устарела.")
+            .show_alert(true)
             .await?;
         return Ok(());
     };
 
+    bot.answer_callback_query(query.id).await?;
+
+    let cache = config.get_redis_client();
     let file_cache_key = format!("transcription_by_file:{}", file_unique_id);
-    let mut cache_entry = match cache.get::<TranscriptionCache>(&file_cache_key).await? {
-        Some(entry) => entry,
-        None => {
-            bot.edit_message_text(
-                message.chat.id,
-                message.id,
-                "❌ Не удалось найти исходное аудио.",
-            )
-                .await?;
-            return Ok(());
-        }
-    };
 
-    if let Some(cached_summary) = cache_entry.summary {
+    if let Some(cached_summary) = cache_entry.summaries.get(&style.key) {
         let final_text = format!(
-            "✨:\n<blockquote expandable>{}</blockquote>",
-            cached_summary
+            "{}:\n<blockquote expandable>{}</blockquote>",
+            style.title,
+            html::escape(cached_summary)
         );
         bot.edit_message_text(message.chat.id, message.id, final_text)
             .parse_mode(ParseMode::Html)
-            .reply_markup(create_summary_keyboard())
-            .await?;
-        return Ok(());
-    }
-
-    if let Some(text) = message.text() && text.contains("[no speech]") {
-        bot.edit_message_text(message.chat.id, message.id, "❌ Нельзя составить краткое содержание из аудио без речи.")
-            .parse_mode(ParseMode::Html)
-            .reply_markup(create_summary_keyboard())
+            .reply_markup(create_summary_keyboard(user_id))
             .await?;
         return Ok(());
     }
@@ -226,7 +297,7 @@ pub async fn summarization_handler(
 
     let file_data_result = save_file_to_memory(&bot, &cache_entry.file_id).await;
     let new_summary_result = match file_data_result {
-        Ok(file_data) => summarize_audio(cache_entry.mime_type.clone(), file_data, config.clone()).await,
+        Ok(file_data) => summarize_audio(cache_entry.mime_type.clone(), file_data, config.clone(), &style.prompt).await,
         Err(e) => {
             error!("Failed to download file for summarization: {:?}", e);
             Err(e)
@@ -235,18 +306,23 @@ pub async fn summarization_handler(
 
     match new_summary_result {
         Ok(new_summary) if !new_summary.is_empty() && !new_summary.contains("Не удалось получить") => {
-            cache_entry.summary = Some(new_summary.clone());
+            cache_entry.summaries.insert(style.key.clone(), new_summary.clone());
             cache.set(&file_cache_key, &cache_entry, 86400).await?;
 
-            let final_text = format!("✨:\n<blockquote expandable>{}</blockquote>", html::escape(&new_summary));
+            let final_text = format!("{}:\n<blockquote expandable>{}</blockquote>", style.title, html::escape(&new_summary));
             bot.edit_message_text(message.chat.id, message.id, final_text)
                 .parse_mode(ParseMode::Html)
-                .reply_markup(create_summary_keyboard())
+                .reply_markup(create_summary_keyboard(user_id))
                 .await?;
         }
         _ => {
             let error_text = "❌ Не удалось составить краткое содержание.";
-            let retry_keyboard = create_retry_keyboard(audio_message_id, user_id, "summarize", cache_entry.attempt);
+            let retry_keyboard = create_retry_keyboard(
+                audio_message_id,
+                user_id,
+                &format!("summarize_{}", style.key),
+                cache_entry.attempt,
+            );
 
             bot.edit_message_text(
                 message.chat.id,
@@ -261,43 +337,474 @@ pub async fn summarization_handler(
     Ok(())
 }
 
+pub async fn export_handler(
+    bot: Bot,
+    query: CallbackQuery,
+    config: &Config,
+    format: &str,
+    user_id: u64,
+) -> Result<(), MyError> {
+    let Some(message) = query.message.as_ref().and_then(|m| m.regular_message()) else {
+        return Ok(());
+    };
+
+    let Some(format) = SubtitleFormat::parse(format) else {
+        bot.answer_callback_query(query.id).await?;
+        return Ok(());
+    };
+
+    if !is_admin_or_author(
+        &bot,
+        message.chat.id,
+        message.chat.is_group() || message.chat.is_supergroup(),
+        &query.from,
+        user_id,
+    )
+        .await
+    {
+        bot.answer_callback_query(query.id)
+            .text("❌ У вас нет прав использовать эту кнопку!")
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    }
+
+    let Some((file_unique_id, mut cache_entry)) =
+        load_transcript(config, message.chat.id, message.id).await?
+    else {
+        bot.answer_callback_query(query.id)
+            .text("❌ Кнопка устарела.")
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    };
+
+    let cache = config.get_redis_client();
+    let file_cache_key = format!("transcription_by_file:{}", file_unique_id);
+
+    let segments = match cache_entry.segments.clone() {
+        Some(segments) => {
+            bot.answer_callback_query(query.id.clone()).await?;
+            segments
+        }
+        None => {
+            bot.answer_callback_query(query.id.clone())
+                .text("⏳ Расставляю таймкоды...")
+                .await?;
+
+            let file_data = save_file_to_memory(&bot, &cache_entry.file_id).await?;
+            let segments = match transcribe_segments(
+                &bot,
+                &cache_entry.mime_type,
+                file_data,
+                config,
+            )
+                .await
+            {
+                Ok(segments) => segments,
+                Err(e) => {
+                    error!("Failed to get transcript segments: {:?}", e);
+                    bot.send_message(message.chat.id, "❌ Не удалось получить таймкоды.")
+                        .reply_parameters(ReplyParameters::new(message.id))
+                        .await?;
+                    return Ok(());
+                }
+            };
+
+            cache_entry.segments = Some(segments.clone());
+            cache.set(&file_cache_key, &cache_entry, 86400).await?;
+            segments
+        }
+    };
+
+    if segments.is_empty() {
+        bot.send_message(message.chat.id, "❌ В аудио не найдено речи.")
+            .reply_parameters(ReplyParameters::new(message.id))
+            .await?;
+        return Ok(());
+    }
+
+    let document = InputFile::memory(format.render(&segments).into_bytes())
+        .file_name(format!("transcript.{}", format.extension()));
+
+    bot.send_document(message.chat.id, document)
+        .reply_parameters(ReplyParameters::new(message.id))
+        .await?;
+
+    Ok(())
+}
+
+// splits long audio into overlapping windows and runs `job` on every window in parallel.
+// None means the audio is short enough (or can't be probed) and should be sent as a whole
+async fn run_chunked<T, F, Fut>(
+    bot: &Bot,
+    config: &Config,
+    data: &Bytes,
+    known_duration: u32,
+    progress: Option<&Message>,
+    job: F,
+) -> Result<Option<Vec<(Window, T)>>, MyError>
+where
+    F: Fn(Bytes) -> Fut,
+    Fut: Future<Output = Result<T, MyError>>,
+{
+    let transcription_config = config.get_json_config().get_transcription_config();
+    let chunk = transcription_config.chunk_seconds as f64;
+    let overlap = transcription_config.chunk_overlap_seconds as f64;
+
+    if known_duration > 0 && known_duration as f64 <= chunk + overlap {
+        return Ok(None);
+    }
+
+    let media = MediaFile::from_bytes(data).await?;
+    let duration = match media.duration().await {
+        Ok(duration) => duration,
+        Err(e) => {
+            warn!("Unable to probe media duration, sending it as a whole: {:?}", e);
+            return Ok(None);
+        }
+    };
+
+    let windows = plan_windows(duration, chunk, overlap);
+    if windows.len() <= 1 {
+        return Ok(None);
+    }
+
+    let total = windows.len();
+    info!("Splitting {:.0}s of audio into {} chunks", duration, total);
+
+    let mut results: Vec<Option<T>> = (0..total).map(|_| None).collect();
+    let mut chunks = stream::iter(windows.clone().into_iter().enumerate())
+        .map(|(i, window)| {
+            let media = &media;
+            let job = &job;
+            async move {
+                let chunk = media.cut_audio(window.start, window.length()).await?;
+                job(chunk).await.map(|result| (i, result))
+            }
+        })
+        .buffer_unordered(transcription_config.max_parallel_chunks.max(1));
+
+    let mut done = 0;
+    while let Some(result) = chunks.next().await {
+        let (i, result) = result?;
+        results[i] = Some(result);
+        done += 1;
+
+        if let Some(message) = progress {
+            let _ = bot
+                .edit_message_text(
+                    message.chat.id,
+                    message.id,
+                    format!("Обрабатываю аудио... {}/{}", done, total),
+                )
+                .await;
+        }
+    }
+
+    Ok(Some(windows.into_iter().zip(results.into_iter().flatten()).collect()))
+}
+
+async fn transcribe_audio(
+    bot: &Bot,
+    file: &AudioStruct,
+    data: Bytes,
+    config: &Config,
+    progress: Option<&Message>,
+) -> Result<String, MyError> {
+    let preprocess_config = &config.get_json_config().get_transcription_config().preprocess;
+    let (mime_type, data) = preprocess_audio(preprocess_config, &file.mime_type, data, true).await;
+
+    let chunked = run_chunked(bot, config, &data, file.duration, progress, |chunk| {
+        let transcription = Transcription {
+            mime_type: "audio/ogg".to_string(),
+            data: chunk,
+            config: config.clone(),
+        };
+        async move { transcription.transcribe().await }
+    })
+        .await?;
+
+    let Some(chunks) = chunked else {
+        let transcription = Transcription {
+            mime_type,
+            data,
+            config: config.clone(),
+        };
+        return transcription.transcribe().await;
+    };
+
+    let texts: Vec<String> = chunks
+        .into_iter()
+        .map(|(_, text)| text)
+        .filter(|text| text.trim() != "[no speech]")
+        .collect();
+
+    if texts.is_empty() {
+        return Ok("[no speech]".to_string());
+    }
+    Ok(merge_texts(&texts))
+}
+
+async fn transcribe_segments(
+    bot: &Bot,
+    mime_type: &str,
+    data: Bytes,
+    config: &Config,
+) -> Result<Vec<TranscriptSegment>, MyError> {
+    let preprocess_config = &config.get_json_config().get_transcription_config().preprocess;
+    let (mime_type, data) = preprocess_audio(preprocess_config, mime_type, data, false).await;
+
+    let provider = config.get_transcription_provider();
+    let chunked = run_chunked(bot, config, &data, 0, None, |chunk| async move {
+        provider.transcribe_segments("audio/ogg", &chunk).await
+    })
+        .await?;
+
+    match chunked {
+        Some(chunks) => Ok(merge_segments(
+            chunks,
+            config
+                .get_json_config()
+                .get_transcription_config()
+                .chunk_overlap_seconds as f64,
+        )),
+        None => provider.transcribe_segments(&mime_type, &data).await,
+    }
+}
+
+// speaker labels are only consistent inside one chunk, so long meetings may mix up speakers between chunks
+async fn transcribe_diarized(
+    bot: &Bot,
+    file: &AudioStruct,
+    data: Bytes,
+    config: &Config,
+    progress: Option<&Message>,
+) -> Result<Vec<TranscriptSegment>, MyError> {
+    let preprocess_config = &config.get_json_config().get_transcription_config().preprocess;
+    let (mime_type, data) = preprocess_audio(preprocess_config, &file.mime_type, data, false).await;
+
+    let provider = config.get_transcription_provider();
+    let chunked = run_chunked(bot, config, &data, file.duration, progress, |chunk| async move {
+        provider.transcribe_diarized("audio/ogg", &chunk).await
+    })
+        .await;
+
+    let result = match chunked {
+        Ok(Some(chunks)) => Ok(merge_segments(
+            chunks,
+            config
+                .get_json_config()
+                .get_transcription_config()
+                .chunk_overlap_seconds as f64,
+        )),
+        Ok(None) => provider.transcribe_diarized(&mime_type, &data).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(mut segments) => {
+            normalize_speakers(&mut segments);
+            Ok(segments)
+        }
+        Err(e) => {
+            error!("Diarized transcription via {} failed: {:?}", provider.name(), e);
+            Err(MyError::Other(format!(
+                "❌ Не удалось преобразовать текст из сообщения.\n\n{}",
+                e
+            )))
+        }
+    }
+}
+
+// message ids repeat across chats, so the chat has to be part of the key
+pub fn message_file_map_key(chat_id: ChatId, message_id: MessageId) -> String {
+    format!("message_file_map:{}:{}", chat_id, message_id)
+}
+
+pub async fn get_transcript_for_message(
+    config: &Config,
+    chat_id: ChatId,
+    message_id: MessageId,
+) -> Result<Option<TranscriptionCache>, MyError> {
+    Ok(load_transcript(config, chat_id, message_id)
+        .await?
+        .map(|(_, entry)| entry))
+}
+
+// redis forgets transcripts after a day, saved history keeps the buttons working after that
+async fn load_transcript(
+    config: &Config,
+    chat_id: ChatId,
+    message_id: MessageId,
+) -> Result<Option<(String, TranscriptionCache)>, MyError> {
+    let cache = config.get_redis_client();
+    let map_key = message_file_map_key(chat_id, message_id);
+    if let Some(file_unique_id) = cache.get::<String>(&map_key).await?
+        && let Some(entry) = cache
+            .get::<TranscriptionCache>(&format!("transcription_by_file:{}", file_unique_id))
+            .await?
+        && !entry.full_text.is_empty()
+    {
+        return Ok(Some((file_unique_id, entry)));
+    }
+
+    let message_ref = Transcript::message_ref(&chat_id.to_string(), message_id.0);
+    let Some(transcript) = Transcript::find_by_message(&message_ref).await? else {
+        return Ok(None);
+    };
+    let Ok(owner_id) = transcript.user_id.parse::<u64>() else {
+        return Ok(None);
+    };
+    if !get_user_speech_settings(owner_id).await.save_history {
+        return Ok(None);
+    }
+
+    let entry = TranscriptionCache {
+        full_text: transcript.text,
+        summaries: HashMap::new(),
+        file_id: transcript.file_id,
+        mime_type: transcript.mime_type,
+        attempt: 0,
+        segments: None,
+        diarized: transcript.diarized,
+    };
+    cache.set(&map_key, &transcript.file_unique_id, 86400).await?;
+    cache
+        .set(
+            &format!("transcription_by_file:{}", transcript.file_unique_id),
+            &entry,
+            86400,
+        )
+        .await?;
+
+    Ok(Some((transcript.file_unique_id, entry)))
+}
+
+pub async fn translate_transcript_handler(
+    bot: Bot,
+    query: CallbackQuery,
+    config: &Config,
+    user_id: u64,
+) -> Result<(), MyError> {
+    let Some(message) = query.message.as_ref().and_then(|m| m.regular_message()) else {
+        return Ok(());
+    };
+
+    if !is_admin_or_author(
+        &bot,
+        message.chat.id,
+        message.chat.is_group() || message.chat.is_supergroup(),
+        &query.from,
+        user_id,
+    )
+        .await
+    {
+        bot.answer_callback_query(query.id)
+            .text("❌ У вас нет прав использовать эту кнопку!")
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    }
+
+    let Some(cache_entry) = get_transcript_for_message(config, message.chat.id, message.id).await? else {
+        bot.answer_callback_query(query.id)
+            .text("❌ Кнопка устарела.")
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    };
+
+    if cache_entry.full_text.contains("[no speech]") {
+        bot.answer_callback_query(query.id)
+            .text("❌ В аудио нет речи для перевода.")
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    }
+
+    bot.answer_callback_query(query.id.clone()).await?;
+
+    // the language picker belongs to whoever pressed the button, not to the author of the audio
+    let job = TranslateJob {
+        text: cache_entry.full_text,
+        user_id: query.from.id.0,
+    };
+    config
+        .get_redis_client()
+        .set(&format!("translate_job:{}", query.from.id), &job, 600)
+        .await?;
+
+    bot.edit_message_text(message.chat.id, message.id, "Выберите язык для перевода:")
+        .reply_markup(create_language_keyboard(0, query.from.id.0))
+        .await?;
+
+    Ok(())
+}
+
+fn too_long_text(max_duration: u32) -> String {
+    let limit = if max_duration.is_multiple_of(60) {
+        format!("{} мин", max_duration / 60)
+    } else {
+        format!("{} сек", max_duration)
+    };
+    format!("❌ Аудио слишком длинное (лимит {}).", limit)
+}
+
 async fn get_cached(
     bot: &Bot,
     file: &AudioStruct,
     config: &Config,
     force_no_cache: bool,
+    diarize: bool,
+    max_duration: u32,
+    progress: Option<&Message>,
 ) -> Result<TranscriptionCache, MyError> {
     let cache = config.get_redis_client();
     let file_cache_key = format!("transcription_by_file:{}", &file.file_unique_id);
 
     if !force_no_cache
         && let Some(cached_text) = cache.get::<TranscriptionCache>(&file_cache_key).await?
-            && !cached_text.full_text.is_empty() {
+            && !cached_text.full_text.is_empty()
+            && cached_text.diarized == diarize {
                 debug!("File cache HIT for unique_id: {}", &file.file_unique_id);
                 return Ok(cached_text);
             }
 
     let file_data = save_file_to_memory(bot, &file.file_id).await?;
-    let transcription = Transcription {
-        mime_type: file.mime_type.to_string(),
-        data: file_data,
-        config: config.clone(),
-    };
-    let processed_parts = transcription.to_text().await;
 
-    if processed_parts.is_empty() || processed_parts[0].contains("Не удалось преобразовать")
-    {
-        let error_message = processed_parts.first().cloned().unwrap_or_default();
-        return Err(MyError::Other(error_message));
+    // documents and retries come without a duration, so the limit is checked on the file itself
+    if max_duration > 0 && file.duration == 0 {
+        match MediaFile::from_bytes(&file_data).await?.duration().await {
+            Ok(duration) if duration > max_duration as f64 => {
+                return Err(MyError::MediaTooLong(max_duration));
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Unable to probe media duration for the limit: {:?}", e),
+        }
     }
 
-    let full_text = processed_parts.join("\n\n");
+    let (full_text, segments) = if diarize {
+        let segments = transcribe_diarized(bot, file, file_data, config, progress).await?;
+        let turns = speaker_turns(&segments);
+        let full_text = if turns.is_empty() {
+            "[no speech]".to_string()
+        } else {
+            turns.join("\n\n")
+        };
+        (full_text, Some(segments))
+    } else {
+        (transcribe_audio(bot, file, file_data, config, progress).await?, None)
+    };
+
     let new_cache_entry = TranscriptionCache {
         full_text,
-        summary: None,
+        summaries: HashMap::new(),
         file_id: file.file_id.clone(),
         mime_type: file.mime_type.clone(),
-        attempt: 0
+        attempt: 0,
+        segments,
+        diarized: diarize,
     };
 
     cache.set(&file_cache_key, &new_cache_entry, 86400).await?;
@@ -309,6 +816,32 @@ async fn get_cached(
     Ok(new_cache_entry)
 }
 
+// history is opt-in, failures here must never break the transcription itself
+async fn remember_transcript(
+    user_id: u64,
+    reply: &Message,
+    file_unique_id: &str,
+    entry: &TranscriptionCache,
+) {
+    if entry.full_text.contains("[no speech]") || !get_user_speech_settings(user_id).await.save_history {
+        return;
+    }
+
+    let transcript = Transcript::new()
+        .user_id(user_id.to_string())
+        .chat_id(reply.chat.id.to_string())
+        .file_unique_id(file_unique_id.to_string())
+        .text(entry.full_text.clone())
+        .file_id(entry.file_id.clone())
+        .mime_type(entry.mime_type.clone())
+        .diarized(entry.diarized)
+        .created_at(chrono::Utc::now().timestamp());
+    let message_ref = Transcript::message_ref(&reply.chat.id.to_string(), reply.id.0);
+    if let Err(e) = transcript.remember(&message_ref).await {
+        error!("Failed to save transcript to history: {:?}", e);
+    }
+}
+
 pub async fn transcription_handler(
     bot: Bot,
     msg: &Message,
@@ -335,34 +868,51 @@ pub async fn transcription_handler(
         return Ok(());
     };
 
+    let settings = get_chat_speech_settings(&msg.chat).await;
+
     if let Some(file) = get_file_id(msg).await {
+        if settings.max_duration > 0 && file.duration > settings.max_duration {
+            bot.edit_message_text(message.chat.id, message.id, too_long_text(settings.max_duration))
+                .await?;
+            return Ok(());
+        }
+
         let cache = config.get_redis_client();
-        let message_file_map_key = format!("message_file_map:{}", message.id);
+        let map_key = message_file_map_key(message.chat.id, message.id);
 
         cache
-            .set(&message_file_map_key, &file.file_unique_id, 86400)
+            .set(&map_key, &file.file_unique_id, 86400)
             .await?;
 
         let file_cache_key = format!("transcription_by_file:{}", &file.file_unique_id);
         let empty_cache = TranscriptionCache {
             full_text: String::new(),
-            summary: None,
+            summaries: HashMap::new(),
             file_id: file.file_id.clone(),
             mime_type: file.mime_type.clone(),
-            attempt: 0
+            attempt: 0,
+            segments: None,
+            diarized: false,
         };
         cache.set(&file_cache_key, &empty_cache, 86400).await?;
 
-        match get_cached(&bot, &file, config, false).await {
+        match get_cached(&bot, &file, config, false, settings.diarization, settings.max_duration, Some(&message)).await {
             Ok(cache_entry) => {
-                let text_parts = split_text(&cache_entry.full_text, 4000);
+                remember_transcript(user.id.0, &message, &file.file_unique_id, &cache_entry).await;
+
+                let text_parts = transcript_pages(&cache_entry);
                 if text_parts.is_empty() {
                     bot.edit_message_text(message.chat.id, message.id, "❌ Получен пустой текст.")
                         .await?;
                     return Ok(());
                 }
 
-                let keyboard = create_transcription_keyboard(0, text_parts.len(), user.id.0);
+                let keyboard = create_transcription_keyboard(
+                    0,
+                    text_parts.len(),
+                    user.id.0,
+                    settings.allow_summaries,
+                );
                 bot.edit_message_text(
                     msg.chat.id,
                     message.id,
@@ -372,16 +922,24 @@ pub async fn transcription_handler(
                     .reply_markup(keyboard)
                     .await?;
             }
+            // retrying can't make the audio any shorter, so there is no retry button
+            Err(MyError::MediaTooLong(limit)) => {
+                bot.edit_message_text(message.chat.id, message.id, too_long_text(limit))
+                    .await?;
+            }
             Err(e) => {
                 error!("Failed to get transcription: {:?}", e);
                 let error_text = match e {
-                    MyError::Other(msg) if msg.contains("❌ Не удалось преобразовать") => {
+                    MyError::Other(msg) if msg.starts_with("❌") => {
                         msg
                     }
-                    MyError::Reqwest(_) => {
-                        "❌ Ошибка: Не удалось скачать файл. Возможно, он слишком большой (>20MB)."
+                    MyError::Teloxide(e) if e.to_string().contains("file is too big") => {
+                        "❌ Ошибка: Файл больше 20 МБ, Telegram не отдаёт такие файлы без локального Bot API сервера."
                             .to_string()
                     }
+                    MyError::Reqwest(_) | MyError::Io(_) => {
+                        "❌ Ошибка: Не удалось скачать файл.".to_string()
+                    }
                     _ => "❌ Произошла неизвестная ошибка при обработке аудио.".to_string(),
                 };
 
@@ -438,7 +996,6 @@ pub async fn retry_speech_handler(
         return Ok(());
     }
 
-    let bot_message_id = message.id.0;
     let new_attempt = attempt + 1;
 
     let Some(replied_to_audio_message_id) = message.reply_to_message().map(|m| m.id.0) else {
@@ -452,9 +1009,9 @@ pub async fn retry_speech_handler(
     };
 
     let cache = config.get_redis_client();
-    let message_file_map_key = format!("message_file_map:{}", bot_message_id);
+    let map_key = message_file_map_key(message.chat.id, message.id);
 
-    let Some(file_unique_id): Option<String> = cache.get::<String>(&message_file_map_key).await? else {
+    let Some(file_unique_id): Option<String> = cache.get::<String>(&map_key).await? else {
         bot.edit_message_text(message.chat.id, message.id, "❌ Кэш для повторной попытки не найден.")
             .await?;
         return Ok(());
@@ -481,15 +1038,23 @@ pub async fn retry_speech_handler(
                 mime_type: cache_entry_template.mime_type,
                 file_id: cache_entry_template.file_id,
                 file_unique_id: file_unique_id.clone(),
+                file_size: 0,
+                duration: 0,
             };
 
             cache.delete(&file_cache_key).await?;
 
-            match get_cached(&bot, &file, config, true).await {
+            let settings = get_chat_speech_settings(&message.chat).await;
+            match get_cached(&bot, &file, config, true, settings.diarization, settings.max_duration, Some(&message)).await {
                 Ok(cache_entry) => {
-                    let text_parts = split_text(&cache_entry.full_text, 4000);
+                    remember_transcript(user_id, &message, &file_unique_id, &cache_entry).await;
+
+                    let text_parts = transcript_pages(&cache_entry);
 
-                    let keyboard = create_transcription_keyboard(0, text_parts.len(), user_id);
+                    let allow_summaries =
+                        get_chat_speech_settings(&message.chat).await.allow_summaries;
+                    let keyboard =
+                        create_transcription_keyboard(0, text_parts.len(), user_id, allow_summaries);
                     bot.edit_message_text(
                         message.chat.id,
                         message.id,
@@ -499,16 +1064,23 @@ pub async fn retry_speech_handler(
                         .reply_markup(keyboard)
                         .await?;
                 }
+                Err(MyError::MediaTooLong(limit)) => {
+                    bot.edit_message_text(message.chat.id, message.id, too_long_text(limit))
+                        .await?;
+                }
                 Err(e) => {
                     error!("Failed to get transcription on retry: {:?}", e);
                     let error_text = match e {
-                        MyError::Other(msg) if msg.contains("❌ Не удалось преобразовать") => {
+                        MyError::Other(msg) if msg.starts_with("❌") => {
                             msg
                         }
-                        MyError::Reqwest(_) => {
-                            "❌ Ошибка: Не удалось скачать файл. Возможно, он слишком большой (>20MB)."
+                        MyError::Teloxide(e) if e.to_string().contains("file is too big") => {
+                            "❌ Ошибка: Файл больше 20 МБ, Telegram не отдаёт такие файлы без локального Bot API сервера."
                                 .to_string()
                         }
+                        MyError::Reqwest(_) | MyError::Io(_) => {
+                            "❌ Ошибка: Не удалось скачать файл.".to_string()
+                        }
                         _ => "❌ Произошла неизвестная ошибка при обработке аудио.".to_string(),
                     };
 
@@ -520,16 +1092,30 @@ pub async fn retry_speech_handler(
 
                     let empty_cache = TranscriptionCache {
                         full_text: String::new(),
-                        summary: None,
+                        summaries: HashMap::new(),
                         file_id: file.file_id.clone(),
                         mime_type: file.mime_type.clone(),
-                        attempt: new_attempt
+                        attempt: new_attempt,
+                        segments: None,
+                        diarized: false,
                     };
                     cache.set(&file_cache_key, &empty_cache, 86400).await?;
                 }
             }
         }
-        "summarize" => {
+        action if action.starts_with("summarize") => {
+            // plain "summarize" comes from buttons sent before summary styles existed
+            let styles = config.get_json_config().get_summary_styles();
+            let style = match action.strip_prefix("summarize_") {
+                Some(key) => config.get_json_config().get_summary_style(key),
+                None => styles.first(),
+            };
+            let Some(style) = style else {
+                bot.edit_message_text(message.chat.id, message.id, "❌ Такого формата больше нет.")
+                    .await?;
+                return Ok(());
+            };
+
             bot.edit_message_text(
                 message.chat.id,
                 message.id,
@@ -550,11 +1136,11 @@ pub async fn retry_speech_handler(
                 }
             };
 
-            cache_entry.summary = None;
+            cache_entry.summaries.remove(&style.key);
 
             let file_data_result = save_file_to_memory(&bot, &cache_entry.file_id).await;
             let new_summary_result = match file_data_result {
-                Ok(file_data) => summarize_audio(cache_entry.mime_type.clone(), file_data, config.clone()).await,
+                Ok(file_data) => summarize_audio(cache_entry.mime_type.clone(), file_data, config.clone(), &style.prompt).await,
                 Err(e) => {
                     error!("Failed to download file for summarization on retry: {:?}", e);
                     Err(e)
@@ -563,14 +1149,14 @@ pub async fn retry_speech_handler(
 
             match new_summary_result {
                 Ok(new_summary) if !new_summary.is_empty() && !new_summary.contains("Не удалось получить") => {
-                    cache_entry.summary = Some(new_summary.clone());
+                    cache_entry.summaries.insert(style.key.clone(), new_summary.clone());
                     cache_entry.attempt = 0;
                     cache.set(&file_cache_key, &cache_entry, 86400).await?;
 
-                    let final_text = format!("✨:\n<blockquote expandable>{}</blockquote>", html::escape(&new_summary));
+                    let final_text = format!("{}:\n<blockquote expandable>{}</blockquote>", style.title, html::escape(&new_summary));
                     bot.edit_message_text(message.chat.id, message.id, final_text)
                         .parse_mode(ParseMode::Html)
-                        .reply_markup(create_summary_keyboard())
+                        .reply_markup(create_summary_keyboard(user_id))
                         .await?;
                 }
                 _ => {
@@ -578,7 +1164,12 @@ pub async fn retry_speech_handler(
 
                     cache_entry.attempt = new_attempt;
 
-                    let retry_keyboard = create_retry_keyboard(replied_to_audio_message_id, user_id, "summarize", cache_entry.attempt);
+                    let retry_keyboard = create_retry_keyboard(
+                        replied_to_audio_message_id,
+                        user_id,
+                        &format!("summarize_{}", style.key),
+                        cache_entry.attempt,
+                    );
 
                     bot.edit_message_text(
                         message.chat.id,
@@ -588,7 +1179,6 @@ pub async fn retry_speech_handler(
                         .reply_markup(retry_keyboard)
                         .await?;
 
-                    cache_entry.summary = None;
                     cache.set(&file_cache_key, &cache_entry, 86400).await?;
                 }
             }
@@ -607,31 +1197,17 @@ pub async fn summarize_audio(
     mime_type: String,
     data: Bytes,
     config: Config,
+    prompt: &str,
 ) -> Result<String, MyError> {
-    let mut settings = Settings::new();
-    settings.set_all_safety_settings(HarmBlockThreshold::BlockNone);
-
-    let ai_model = config.get_json_config().get_ai_model().to_owned();
-    let prompt = config.get_json_config().get_summarize_prompt().to_owned();
-
-    let mut context = Context::new();
-    context.push_message(Role::Model, prompt);
-
-    let mut client = GemSession::Builder()
-        .model(Models::Custom(ai_model))
-        .timeout(Some(Duration::from_secs(120)))
-        .context(context)
-        .build();
-
-    let response = client
-        .send_blob(Blob::new(&mime_type, &data), Role::User, &settings)
+    let summary = config
+        .get_transcription_provider()
+        .summarize(&mime_type, &data, prompt)
         .await?;
 
-    Ok(response
-        .get_results()
-        .first()
-        .cloned()
-        .unwrap_or_else(|| "❌ Не удалось получить краткое содержание.".to_string()))
+    if summary.is_empty() {
+        return Ok("❌ Не удалось получить краткое содержание.".to_string());
+    }
+    Ok(summary)
 }
 
 pub async fn get_file_id(msg: &Message) -> Option<AudioStruct> {
@@ -641,17 +1217,47 @@ pub async fn get_file_id(msg: &Message) -> Option<AudioStruct> {
                 mime_type: audio.audio.mime_type.as_ref()?.essence_str().to_owned(),
                 file_id: audio.audio.file.id.0.to_string(),
                 file_unique_id: audio.audio.file.unique_id.0.to_string(),
+                file_size: audio.audio.file.size,
+                duration: audio.audio.duration.seconds(),
             }),
             teloxide::types::MediaKind::Voice(voice) => Some(AudioStruct {
                 mime_type: voice.voice.mime_type.as_ref()?.essence_str().to_owned(),
                 file_id: voice.voice.file.id.0.to_owned(),
                 file_unique_id: voice.voice.file.unique_id.0.to_owned(),
+                file_size: voice.voice.file.size,
+                duration: voice.voice.duration.seconds(),
             }),
             teloxide::types::MediaKind::VideoNote(video_note) => Some(AudioStruct {
                 mime_type: "video/mp4".to_owned(),
                 file_id: video_note.video_note.file.id.0.to_owned(),
                 file_unique_id: video_note.video_note.file.unique_id.0.to_owned(),
+                file_size: video_note.video_note.file.size,
+                duration: video_note.video_note.duration.seconds(),
+            }),
+            teloxide::types::MediaKind::Video(video) => Some(AudioStruct {
+                mime_type: video
+                    .video
+                    .mime_type
+                    .as_ref()
+                    .map_or("video/mp4".to_owned(), |m| m.essence_str().to_owned()),
+                file_id: video.video.file.id.0.to_owned(),
+                file_unique_id: video.video.file.unique_id.0.to_owned(),
+                file_size: video.video.file.size,
+                duration: video.video.duration.seconds(),
             }),
+            teloxide::types::MediaKind::Document(document) => {
+                let mime_type = document.document.mime_type.as_ref()?.essence_str().to_owned();
+                if !(mime_type.starts_with("audio/") || mime_type.starts_with("video/")) {
+                    return None;
+                }
+                Some(AudioStruct {
+                    mime_type,
+                    file_id: document.document.file.id.0.to_owned(),
+                    file_unique_id: document.document.file.unique_id.0.to_owned(),
+                    file_size: document.document.file.size,
+                    duration: 0, // unknown for documents
+                })
+            }
             _ => None,
         },
         _ => None,
@@ -660,52 +1266,34 @@ pub async fn get_file_id(msg: &Message) -> Option<AudioStruct> {
 
 pub async fn save_file_to_memory(bot: &Bot, file_id: &str) -> Result<Bytes, MyError> {
     let file = bot.get_file(FileId(file_id.to_string())).send().await?;
-    let file_url = format!(
-        "https://api.telegram.org/file/bot{}/{}",
-        bot.token(),
-        file.path
-    );
+    // a local bot api server in --local mode hands out paths on its own disk, that's also
+    // the only way to get files over 20MB
+    if std::path::Path::new(&file.path).is_absolute() {
+        return Ok(Bytes::from(tokio::fs::read(&file.path).await?));
+    }
+    let file_url = bot
+        .api_url()
+        .join(&format!("file/bot{}/{}", bot.token(), file.path))?;
     let response = reqwest::get(file_url).await?;
     Ok(response.bytes().await?)
 }
 
 impl Transcription {
-    pub async fn to_text(&self) -> Vec<String> {
-        let mut settings = Settings::new();
-        settings.set_all_safety_settings(HarmBlockThreshold::BlockNone);
-
+    pub async fn transcribe(&self) -> Result<String, MyError> {
         let error_answer = "❌ Не удалось преобразовать текст из сообщения.".to_string();
-        let ai_model = self.config.get_json_config().get_ai_model().to_owned();
-        let prompt = self.config.get_json_config().get_ai_prompt().to_owned();
-
-        let mut context = Context::new();
-        context.push_message(Role::Model, prompt);
-
-        let mut client = GemSession::Builder()
-            .model(Models::Custom(ai_model))
-            .timeout(Some(Duration::from_secs(120)))
-            .context(context)
-            .build();
+        let provider = self.config.get_transcription_provider();
 
         let mut attempts = 0;
         let mut last_error = String::new();
 
         while attempts < 3 {
-            match client
-                .send_blob(
-                    Blob::new(&self.mime_type, &self.data),
-                    Role::User,
-                    &settings,
-                )
-                .await
-            {
-                Ok(response) => {
-                    let full_text = response.get_results().first().cloned().unwrap_or_default();
+            match provider.transcribe(&self.mime_type, &self.data).await {
+                Ok(full_text) => {
                     if !full_text.is_empty() {
-                        return split_text(&full_text, 4000);
+                        return Ok(full_text);
                     }
                     attempts += 1;
-                    info!("Received empty response, attempt {}", attempts);
+                    info!("Received empty response from {}, attempt {}", provider.name(), attempts);
                 }
                 Err(error) => {
                     attempts += 1;
@@ -714,10 +1302,15 @@ impl Transcription {
                         continue;
                     }
                     last_error = error_string;
-                    error!("Transcription error (attempt {}): {:?}", attempts, error);
+                    error!(
+                        "Transcription error via {} (attempt {}): {:?}",
+                        provider.name(),
+                        attempts,
+                        error
+                    );
                 }
             }
         }
-        vec![error_answer + "\n\n" + &last_error]
+        Err(MyError::Other(error_answer + "\n\n" + &last_error))
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,91 @@
+use crate::core::services::transcription::TranscriptSegment;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Window {
+    pub start: f64,
+    pub end: f64,
+}
+
+impl Window {
+    pub fn length(&self) -> f64 {
+        self.end - self.start
+    }
+}
+
+// every window is `chunk` long plus `overlap` that repeats in the next one
+pub fn plan_windows(duration: f64, chunk: f64, overlap: f64) -> Vec<Window> {
+    if duration <= chunk + overlap {
+        return vec![Window { start: 0.0, end: duration }];
+    }
+
+    let mut windows = Vec::new();
+    let mut start = 0.0;
+    loop {
+        let end = (start + chunk + overlap).min(duration);
+        windows.push(Window { start, end });
+        if end >= duration {
+            break;
+        }
+        start += chunk;
+    }
+    windows
+}
+
+fn normalize_word(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+fn overlap_len(prev: &[&str], next: &[&str]) -> usize {
+    let max = prev.len().min(next.len()).min(40);
+    (2..=max)
+        .rev()
+        .find(|&k| {
+            prev[prev.len() - k..]
+                .iter()
+                .zip(&next[..k])
+                .all(|(a, b)| normalize_word(a) == normalize_word(b))
+        })
+        .unwrap_or(0)
+}
+
+// drops the words that got transcribed twice because of the window overlap
+pub fn merge_texts(parts: &[String]) -> String {
+    let mut merged: Vec<&str> = Vec::new();
+
+    for part in parts {
+        let words: Vec<&str> = part.split_whitespace().collect();
+        let skip = overlap_len(&merged, &words);
+        merged.extend_from_slice(&words[skip..]);
+    }
+
+    merged.join(" ")
+}
+
+pub fn merge_segments(
+    chunks: Vec<(Window, Vec<TranscriptSegment>)>,
+    overlap: f64,
+) -> Vec<TranscriptSegment> {
+    let total = chunks.len();
+    let mut merged = Vec::new();
+
+    for (i, (window, segments)) in chunks.into_iter().enumerate() {
+        // cut in the middle of the overlapped part, so every moment belongs to exactly one window
+        let lower = if i == 0 { f64::MIN } else { window.start + overlap / 2.0 };
+        let upper = if i + 1 == total { f64::MAX } else { window.end - overlap / 2.0 };
+
+        merged.extend(segments.into_iter().filter_map(|segment| {
+            let start = segment.start + window.start;
+            (start >= lower && start < upper).then_some(TranscriptSegment {
+                speaker: segment.speaker,
+                start,
+                end: segment.end + window.start,
+                text: segment.text,
+            })
+        }));
+    }
+
+    merged
+}
@@ -0,0 +1,44 @@
+use crate::core::services::transcription::TranscriptSegment;
+use std::collections::HashMap;
+
+// providers name speakers however they like ("A", "spk_0", "Speaker 1"), so relabel them by first appearance
+pub fn normalize_speakers(segments: &mut [TranscriptSegment]) {
+    let mut labels: HashMap<String, String> = HashMap::new();
+
+    for segment in segments.iter_mut() {
+        let raw = segment.speaker.clone().unwrap_or_default();
+        let next = labels.len() + 1;
+        let label = labels
+            .entry(raw)
+            .or_insert_with(|| format!("Speaker {}", next));
+        segment.speaker = Some(label.clone());
+    }
+}
+
+// glues consecutive segments of the same speaker into one "Speaker N: ..." paragraph
+pub fn speaker_turns(segments: &[TranscriptSegment]) -> Vec<String> {
+    let mut turns: Vec<(Option<&str>, String)> = Vec::new();
+
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        match turns.last_mut() {
+            Some((speaker, turn)) if *speaker == segment.speaker.as_deref() => {
+                turn.push(' ');
+                turn.push_str(text);
+            }
+            _ => turns.push((segment.speaker.as_deref(), text.to_string())),
+        }
+    }
+
+    turns
+        .into_iter()
+        .map(|(speaker, text)| match speaker {
+            Some(speaker) => format!("{}: {}", speaker, text),
+            None => text,
+        })
+        .collect()
+}
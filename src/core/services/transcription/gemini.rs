@@ -0,0 +1,105 @@
+use crate::{
+    core::services::transcription::{TranscriptSegment, TranscriptionProvider},
+    errors::MyError,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use gem_rs::{
+    api::Models,
+    client::GemSession,
+    types::{Blob, Context, HarmBlockThreshold, Role, Settings},
+};
+use std::time::Duration;
+
+pub struct GeminiProvider {
+    model: String,
+    prompt: String,
+    segments_prompt: String,
+    diarization_prompt: String,
+}
+
+fn parse_segments(response: &str) -> Result<Vec<TranscriptSegment>, MyError> {
+    // gemini likes to wrap json into ```json fences even when asked not to
+    let raw = response
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+
+    Ok(serde_json::from_str(raw)?)
+}
+
+impl GeminiProvider {
+    pub fn new(
+        model: String,
+        prompt: String,
+        segments_prompt: String,
+        diarization_prompt: String,
+    ) -> Self {
+        Self {
+            model,
+            prompt,
+            segments_prompt,
+            diarization_prompt,
+        }
+    }
+
+    async fn send(&self, mime_type: &str, data: &Bytes, prompt: &str) -> Result<String, MyError> {
+        let mut settings = Settings::new();
+        settings.set_all_safety_settings(HarmBlockThreshold::BlockNone);
+
+        let mut context = Context::new();
+        context.push_message(Role::Model, prompt.to_string());
+
+        let mut client = GemSession::Builder()
+            .model(Models::Custom(self.model.clone()))
+            .timeout(Some(Duration::from_secs(120)))
+            .context(context)
+            .build();
+
+        let response = client
+            .send_blob(Blob::new(mime_type, data), Role::User, &settings)
+            .await?;
+
+        Ok(response.get_results().first().cloned().unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for GeminiProvider {
+    fn name(&self) -> &'static str {
+        "gemini"
+    }
+
+    async fn transcribe(&self, mime_type: &str, data: &Bytes) -> Result<String, MyError> {
+        self.send(mime_type, data, &self.prompt).await
+    }
+
+    async fn transcribe_segments(
+        &self,
+        mime_type: &str,
+        data: &Bytes,
+    ) -> Result<Vec<TranscriptSegment>, MyError> {
+        let response = self.send(mime_type, data, &self.segments_prompt).await?;
+        parse_segments(&response)
+    }
+
+    async fn transcribe_diarized(
+        &self,
+        mime_type: &str,
+        data: &Bytes,
+    ) -> Result<Vec<TranscriptSegment>, MyError> {
+        let response = self.send(mime_type, data, &self.diarization_prompt).await?;
+        parse_segments(&response)
+    }
+
+    async fn summarize(
+        &self,
+        mime_type: &str,
+        data: &Bytes,
+        prompt: &str,
+    ) -> Result<String, MyError> {
+        self.send(mime_type, data, prompt).await
+    }
+}
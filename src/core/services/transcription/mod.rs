@@ -0,0 +1,77 @@
+pub mod chunking;
+pub mod diarization;
+pub mod gemini;
+pub mod openai;
+pub mod preprocess;
+pub mod subtitles;
+
+use crate::{
+    core::{
+        config::json::{JsonConfig, TranscriptionProviderKind},
+        services::transcription::{gemini::GeminiProvider, openai::OpenAiProvider},
+    },
+    errors::MyError,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TranscriptSegment {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+#[async_trait]
+pub trait TranscriptionProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn transcribe(&self, mime_type: &str, data: &Bytes) -> Result<String, MyError>;
+
+    async fn transcribe_segments(
+        &self,
+        mime_type: &str,
+        data: &Bytes,
+    ) -> Result<Vec<TranscriptSegment>, MyError>;
+
+    async fn transcribe_diarized(
+        &self,
+        mime_type: &str,
+        data: &Bytes,
+    ) -> Result<Vec<TranscriptSegment>, MyError>;
+
+    async fn summarize(&self, mime_type: &str, data: &Bytes, prompt: &str)
+    -> Result<String, MyError>;
+}
+
+pub fn build_provider(
+    json_config: &JsonConfig,
+    api_key: Option<String>,
+) -> Arc<dyn TranscriptionProvider> {
+    let config = json_config.get_transcription_config();
+    match config.provider {
+        TranscriptionProviderKind::Gemini => Arc::new(GeminiProvider::new(
+            config
+                .model
+                .clone()
+                .unwrap_or_else(|| json_config.get_ai_model().to_string()),
+            json_config.get_ai_prompt().to_string(),
+            json_config.get_segments_prompt().to_string(),
+            json_config.get_diarization_prompt().to_string(),
+        )),
+        TranscriptionProviderKind::OpenAi => Arc::new(OpenAiProvider::new(
+            config
+                .base_url
+                .clone()
+                .unwrap_or_else(|| "https://api.openai.com/v1".to_string()),
+            config.model.clone().unwrap_or_else(|| "whisper-1".to_string()),
+            config.summary_model.clone(),
+            config.diarization_model.clone(),
+            api_key,
+        )),
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{
+    core::services::transcription::{TranscriptSegment, TranscriptionProvider},
+    errors::MyError,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::{
+    Client, RequestBuilder, Response,
+    multipart::{Form, Part},
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+#[derive(Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct VerboseTranscriptionResponse {
+    #[serde(default)]
+    segments: Vec<TranscriptSegment>,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+// works with anything that speaks the OpenAI audio api: openai itself, whisper.cpp server, faster-whisper-server, etc
+pub struct OpenAiProvider {
+    client: Client,
+    base_url: String,
+    model: String,
+    summary_model: Option<String>,
+    diarization_model: Option<String>,
+    api_key: Option<String>,
+}
+
+fn file_name_for(mime_type: &str) -> &'static str {
+    match mime_type {
+        "audio/ogg" | "audio/opus" => "audio.ogg",
+        "audio/mpeg" | "audio/mp3" => "audio.mp3",
+        "audio/mp4" | "audio/x-m4a" | "audio/m4a" => "audio.m4a",
+        "audio/wav" | "audio/x-wav" => "audio.wav",
+        "audio/webm" => "audio.webm",
+        "audio/flac" => "audio.flac",
+        "video/mp4" => "video.mp4",
+        _ => "audio.bin",
+    }
+}
+
+impl OpenAiProvider {
+    pub fn new(
+        base_url: String,
+        model: String,
+        summary_model: Option<String>,
+        diarization_model: Option<String>,
+        api_key: Option<String>,
+    ) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(300))
+                .build()
+                .unwrap_or_default(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model,
+            summary_model,
+            diarization_model,
+            api_key,
+        }
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+
+    async fn request_transcription(
+        &self,
+        mime_type: &str,
+        data: &Bytes,
+        response_format: &str,
+    ) -> Result<Response, MyError> {
+        let model = match response_format {
+            "diarized_json" => self.diarization_model.clone().ok_or_else(|| {
+                MyError::Other(
+                    "Diarization model is not configured for the openai provider".to_string(),
+                )
+            })?,
+            _ => self.model.clone(),
+        };
+
+        let file = Part::bytes(data.to_vec())
+            .file_name(file_name_for(mime_type))
+            .mime_str(mime_type)?;
+        let mut form = Form::new()
+            .part("file", file)
+            .text("model", model)
+            .text("response_format", response_format.to_string());
+        match response_format {
+            "verbose_json" => form = form.text("timestamp_granularities[]", "segment"),
+            "diarized_json" => form = form.text("chunking_strategy", "auto"),
+            _ => {}
+        }
+
+        let response = self
+            .authorize(
+                self.client
+                    .post(format!("{}/audio/transcriptions", self.base_url)),
+            )
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(MyError::Other(format!(
+                "Transcription API returned {}: {}",
+                status, body
+            )));
+        }
+
+        Ok(response)
+    }
+
+    async fn complete(&self, model: &str, prompt: &str, text: &str) -> Result<String, MyError> {
+        let body = json!({
+            "model": model,
+            "messages": [
+                { "role": "system", "content": prompt },
+                { "role": "user", "content": text },
+            ],
+        });
+
+        let response = self
+            .authorize(self.client.post(format!("{}/chat/completions", self.base_url)))
+            .json(&body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(MyError::Other(format!(
+                "Chat completion API returned {}: {}",
+                status, body
+            )));
+        }
+
+        let parsed = response.json::<ChatCompletionResponse>().await?;
+        Ok(parsed
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for OpenAiProvider {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    async fn transcribe(&self, mime_type: &str, data: &Bytes) -> Result<String, MyError> {
+        let response = self.request_transcription(mime_type, data, "json").await?;
+        let parsed = response.json::<TranscriptionResponse>().await?;
+        Ok(parsed.text.trim().to_string())
+    }
+
+    async fn transcribe_segments(
+        &self,
+        mime_type: &str,
+        data: &Bytes,
+    ) -> Result<Vec<TranscriptSegment>, MyError> {
+        let response = self
+            .request_transcription(mime_type, data, "verbose_json")
+            .await?;
+        let parsed = response.json::<VerboseTranscriptionResponse>().await?;
+        Ok(parsed.segments)
+    }
+
+    async fn transcribe_diarized(
+        &self,
+        mime_type: &str,
+        data: &Bytes,
+    ) -> Result<Vec<TranscriptSegment>, MyError> {
+        let response = self
+            .request_transcription(mime_type, data, "diarized_json")
+            .await?;
+        let parsed = response.json::<VerboseTranscriptionResponse>().await?;
+        Ok(parsed.segments)
+    }
+
+    async fn summarize(
+        &self,
+        mime_type: &str,
+        data: &Bytes,
+        prompt: &str,
+    ) -> Result<String, MyError> {
+        let Some(summary_model) = &self.summary_model else {
+            return Err(MyError::Other(
+                "Summary model is not configured for the openai provider".to_string(),
+            ));
+        };
+
+        let transcript = self.transcribe(mime_type, data).await?;
+        if transcript.is_empty() {
+            return Ok(String::new());
+        }
+
+        self.complete(summary_model, prompt, &transcript).await
+    }
+}
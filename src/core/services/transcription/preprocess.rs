@@ -0,0 +1,95 @@
+use crate::core::{config::json::PreprocessConfig, services::ffmpeg::MediaFile};
+use bytes::Bytes;
+use log::{info, warn};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub struct PreprocessStats {
+    files: AtomicU64,
+    bytes_in: AtomicU64,
+    bytes_out: AtomicU64,
+}
+
+impl PreprocessStats {
+    const fn new() -> Self {
+        Self {
+            files: AtomicU64::new(0),
+            bytes_in: AtomicU64::new(0),
+            bytes_out: AtomicU64::new(0),
+        }
+    }
+
+    fn record(&self, bytes_in: usize, bytes_out: usize) {
+        self.files.fetch_add(1, Ordering::Relaxed);
+        self.bytes_in.fetch_add(bytes_in as u64, Ordering::Relaxed);
+        self.bytes_out.fetch_add(bytes_out as u64, Ordering::Relaxed);
+    }
+
+    pub fn files(&self) -> u64 {
+        self.files.load(Ordering::Relaxed)
+    }
+
+    // can go negative if already tiny voice notes get re-encoded a bit bigger
+    pub fn saved_bytes(&self) -> i64 {
+        self.bytes_in.load(Ordering::Relaxed) as i64 - self.bytes_out.load(Ordering::Relaxed) as i64
+    }
+}
+
+pub static PREPROCESS_STATS: PreprocessStats = PreprocessStats::new();
+
+fn build_filters(config: &PreprocessConfig, trim_silence: bool) -> Vec<String> {
+    let mut filters = Vec::new();
+
+    if config.normalize_loudness {
+        filters.push("loudnorm=I=-16:TP=-1.5:LRA=11".to_string());
+    }
+
+    // keeps a short pause instead of gluing words together
+    if trim_silence && config.trim_silence {
+        filters.push(format!(
+            "silenceremove=start_periods=1:start_threshold={t}dB:stop_periods=-1:stop_duration={d}:stop_threshold={t}dB:stop_silence=0.3",
+            t = config.silence_threshold_db,
+            d = config.min_silence_seconds,
+        ));
+    }
+
+    filters
+}
+
+// silence trimming shifts timestamps, so anything that needs real timings has to pass `trim_silence = false`
+pub async fn preprocess_audio(
+    config: &PreprocessConfig,
+    mime_type: &str,
+    data: Bytes,
+    trim_silence: bool,
+) -> (String, Bytes) {
+    if !config.enabled {
+        return (mime_type.to_string(), data);
+    }
+
+    let result = match MediaFile::from_bytes(&data).await {
+        Ok(media) => media.filter_audio(&build_filters(config, trim_silence)).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(processed) if !processed.is_empty() => {
+            PREPROCESS_STATS.record(data.len(), processed.len());
+            info!(
+                "Preprocessed {} audio: {} -> {} bytes ({} bytes saved in total)",
+                mime_type,
+                data.len(),
+                processed.len(),
+                PREPROCESS_STATS.saved_bytes()
+            );
+            ("audio/ogg".to_string(), processed)
+        }
+        Ok(_) => {
+            warn!("Preprocessing produced no audio, sending the original file");
+            (mime_type.to_string(), data)
+        }
+        Err(e) => {
+            warn!("Audio preprocessing failed, sending the original file: {:?}", e);
+            (mime_type.to_string(), data)
+        }
+    }
+}
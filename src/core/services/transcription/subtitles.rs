@@ -0,0 +1,81 @@
+use crate::core::services::transcription::TranscriptSegment;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+}
+
+impl SubtitleFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "srt" => Some(SubtitleFormat::Srt),
+            "vtt" => Some(SubtitleFormat::Vtt),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SubtitleFormat::Srt => "srt",
+            SubtitleFormat::Vtt => "vtt",
+        }
+    }
+
+    pub fn render(&self, segments: &[TranscriptSegment]) -> String {
+        match self {
+            SubtitleFormat::Srt => to_srt(segments),
+            SubtitleFormat::Vtt => to_vtt(segments),
+        }
+    }
+}
+
+fn cue_text(segment: &TranscriptSegment) -> String {
+    match &segment.speaker {
+        Some(speaker) => format!("{}: {}", speaker, segment.text.trim()),
+        None => segment.text.trim().to_string(),
+    }
+}
+
+fn format_timestamp(seconds: f64, separator: char) -> String {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    let hours = total_ms / 3_600_000;
+    let minutes = (total_ms % 3_600_000) / 60_000;
+    let secs = (total_ms % 60_000) / 1000;
+    let ms = total_ms % 1000;
+    format!("{:02}:{:02}:{:02}{}{:03}", hours, minutes, secs, separator, ms)
+}
+
+pub fn to_srt(segments: &[TranscriptSegment]) -> String {
+    segments
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| {
+            format!(
+                "{}\n{} --> {}\n{}\n",
+                i + 1,
+                format_timestamp(segment.start, ','),
+                format_timestamp(segment.end, ','),
+                cue_text(segment)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn to_vtt(segments: &[TranscriptSegment]) -> String {
+    let cues = segments
+        .iter()
+        .map(|segment| {
+            format!(
+                "{} --> {}\n{}\n",
+                format_timestamp(segment.start, '.'),
+                format_timestamp(segment.end, '.'),
+                cue_text(segment)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!("WEBVTT\n\n{}", cues)
+}
@@ -0,0 +1,208 @@
+use crate::{
+    core::{
+        config::{Config, json::DownloadsConfig},
+        services::{
+            cobalt::{AudioBitrate, AudioFormat, VideoQuality},
+            ffmpeg::MediaFile,
+        },
+    },
+    errors::MyError,
+};
+use log::{debug, error, warn};
+use reqwest::Client;
+use std::time::Duration;
+use teloxide::{
+    prelude::*,
+    types::{FileId, InputFile, Message},
+};
+use tokio::io::AsyncWriteExt;
+
+// either a file telegram already has, or one downloaded here that still has to be uploaded
+pub enum MediaSource {
+    Cached(String),
+    Local(MediaFile),
+}
+
+impl MediaSource {
+    pub fn input_file(&self) -> InputFile {
+        match self {
+            MediaSource::Cached(file_id) => InputFile::file_id(FileId(file_id.clone())),
+            MediaSource::Local(file) => InputFile::file(file.path().to_path_buf()),
+        }
+    }
+}
+
+pub fn file_cache_key(original_url: &str, quality: &VideoQuality) -> String {
+    format!("cobalt_file:{}:{:x}", quality.as_str(), md5::compute(original_url))
+}
+
+// carousel items have no url of their own, only a position in the post
+pub fn gallery_cache_key(original_url: &str, index: usize) -> String {
+    format!("cobalt_file:item{}:{:x}", index, md5::compute(original_url))
+}
+
+pub fn audio_cache_key(original_url: &str, format: &AudioFormat, bitrate: &AudioBitrate) -> String {
+    format!(
+        "cobalt_audio_file:{}:{}:{:x}",
+        format.as_str(),
+        bitrate.as_str(),
+        md5::compute(original_url)
+    )
+}
+
+pub fn sent_file_id(message: &Message) -> Option<String> {
+    message
+        .video()
+        .map(|video| &video.file.id)
+        .or_else(|| message.audio().map(|audio| &audio.file.id))
+        .or_else(|| message.animation().map(|animation| &animation.file.id))
+        .or_else(|| message.document().map(|document| &document.file.id))
+        .map(|file_id| file_id.to_string())
+}
+
+// streamed to disk chunk by chunk, so a huge file is dropped as soon as it crosses the limit
+async fn download_to_file(url: &str, limits: &DownloadsConfig) -> Result<MediaFile, MyError> {
+    let max_bytes = limits.max_size_mb * 1024 * 1024;
+    let client = Client::builder()
+        .timeout(Duration::from_secs(limits.timeout_secs))
+        .build()?;
+
+    let mut response = client.get(url).send().await?.error_for_status()?;
+    if response.content_length().is_some_and(|length| length > max_bytes) {
+        return Err(MyError::MediaTooLarge(limits.max_size_mb));
+    }
+
+    let file = MediaFile::temp();
+    let mut output = tokio::fs::File::create(file.path()).await?;
+    let mut written = 0u64;
+
+    while let Some(chunk) = response.chunk().await? {
+        written += chunk.len() as u64;
+        if written > max_bytes {
+            return Err(MyError::MediaTooLarge(limits.max_size_mb));
+        }
+        output.write_all(&chunk).await?;
+    }
+    output.flush().await?;
+
+    Ok(file)
+}
+
+async fn cached_file_id(config: &Config, cache_key: &str) -> Option<String> {
+    let file_id = config.get_redis_client().get::<String>(cache_key).await.ok()??;
+    debug!("Reusing uploaded file for {}", cache_key);
+    Some(file_id)
+}
+
+pub async fn prepare_video(
+    config: &Config,
+    video_url: &str,
+    cache_key: &str,
+) -> Result<MediaSource, MyError> {
+    if let Some(file_id) = cached_file_id(config, cache_key).await {
+        return Ok(MediaSource::Cached(file_id));
+    }
+
+    let limits = config.get_json_config().get_downloads_config();
+    let mut file = download_to_file(video_url, limits).await?;
+
+    if limits.transcode {
+        match file.to_telegram_mp4().await {
+            Ok(Some(converted)) => file = converted,
+            Ok(None) => {}
+            // the original still has a chance to be accepted as is
+            Err(e) => error!("Failed to convert {} to mp4: {}", video_url, e),
+        }
+        if file.size().await? > limits.max_size_mb * 1024 * 1024 {
+            return Err(MyError::MediaTooLarge(limits.max_size_mb));
+        }
+    }
+
+    Ok(MediaSource::Local(file))
+}
+
+// cobalt already hands out the requested format, so audio is uploaded as is
+pub async fn prepare_audio(
+    config: &Config,
+    audio_url: &str,
+    cache_key: &str,
+) -> Result<MediaSource, MyError> {
+    if let Some(file_id) = cached_file_id(config, cache_key).await {
+        return Ok(MediaSource::Cached(file_id));
+    }
+
+    let limits = config.get_json_config().get_downloads_config();
+    Ok(MediaSource::Local(download_to_file(audio_url, limits).await?))
+}
+
+pub async fn remember_file_id(config: &Config, cache_key: &str, message: &Message) {
+    let Some(file_id) = sent_file_id(message) else {
+        return;
+    };
+    let ttl = config.get_json_config().get_downloads_config().file_id_ttl_days * 24 * 60 * 60;
+    if let Err(e) = config
+        .get_redis_client()
+        .set(cache_key, &file_id, ttl as usize)
+        .await
+    {
+        error!("Failed to cache uploaded file id: {}", e);
+    }
+}
+
+// inline messages can't take a fresh upload, so new files go through the storage chat first
+async fn take_uploaded_file_id(
+    config: &Config,
+    cache_key: &str,
+    sent: &Message,
+) -> Result<String, MyError> {
+    remember_file_id(config, cache_key, sent).await;
+
+    let file_id = sent_file_id(sent).ok_or("Uploaded media has no file id")?;
+    if let Err(e) = config.get_bot().delete_message(sent.chat.id, sent.id).await {
+        warn!("Failed to clean up uploaded media in the storage chat: {}", e);
+    }
+    Ok(file_id)
+}
+
+pub async fn upload_video_to_storage(
+    config: &Config,
+    video_url: &str,
+    cache_key: &str,
+) -> Result<String, MyError> {
+    let video = match prepare_video(config, video_url, cache_key).await? {
+        MediaSource::Cached(file_id) => return Ok(file_id),
+        video => video,
+    };
+
+    let sent = config
+        .get_bot()
+        .send_video(config.get_storage_chat_id(), video.input_file())
+        .supports_streaming(true)
+        .await?;
+    take_uploaded_file_id(config, cache_key, &sent).await
+}
+
+pub async fn upload_audio_to_storage(
+    config: &Config,
+    audio_url: &str,
+    filename: &str,
+    cache_key: &str,
+    as_audio: bool,
+) -> Result<String, MyError> {
+    let audio = match prepare_audio(config, audio_url, cache_key).await? {
+        MediaSource::Cached(file_id) => return Ok(file_id),
+        audio => audio,
+    };
+
+    let mut input_file = audio.input_file();
+    if !filename.is_empty() {
+        input_file = input_file.file_name(filename.to_string());
+    }
+    let bot = config.get_bot();
+    let sent = if as_audio {
+        bot.send_audio(config.get_storage_chat_id(), input_file).await?
+    } else {
+        bot.send_document(config.get_storage_chat_id(), input_file).await?
+    };
+    take_uploaded_file_id(config, cache_key, &sent).await
+}
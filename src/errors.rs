@@ -29,6 +29,9 @@ pub enum MyError {
     #[error("MongoDB Error: {0}")]
     MongoDb(#[from] OxiModError),
 
+    #[error("MongoDB query error: {0}")]
+    MongoQuery(#[from] mongodb::error::Error),
+
     #[error("Failed to parse URL: {0}")]
     UrlParse(#[from] ParseError),
 
@@ -59,11 +62,20 @@ pub enum MyError {
     #[error("UTF-8 conversion error: {0}")]
     Utf8(#[from] FromUtf8Error),
 
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
     #[error("Serde json error: {0}")]
     SerdeJson(#[from] serde_json::Error),
 
     #[error("User not found")]
     UserNotFound,
+
+    #[error("Media is larger than {0} MB")]
+    MediaTooLarge(u64),
+
+    #[error("Media is longer than {0} seconds")]
+    MediaTooLong(u32),
 }
 
 impl From<&str> for MyError {
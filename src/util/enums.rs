@@ -11,10 +11,22 @@ pub enum Command {
     Translate(String),
     #[command(description = "Bot settings")]
     Settings,
+    #[command(description = "Transcription history")]
+    Transcripts(String),
+    #[command(description = "Currency rate chart")]
+    Chart(String),
+    #[command(description = "Rate alerts and daily digest")]
+    Alert(String),
+    #[command(description = "Custom currency names")]
+    Alias(String),
+    #[command(hide)]
+    ReloadCurrencies,
 }
 
 pub struct AudioStruct {
     pub mime_type: String,
     pub file_id: String,
     pub file_unique_id: String,
+    pub file_size: u32,
+    pub duration: u32,
 }
@@ -1,6 +1,6 @@
 use teloxide::Bot;
 use teloxide::prelude::{ChatId, Requester};
-use teloxide::types::User;
+use teloxide::types::{Message, User};
 
 pub mod currency_values;
 pub mod enums;
@@ -17,6 +17,40 @@ pub fn split_text(text: &str, chunk_size: usize) -> Vec<String> {
         .collect()
 }
 
+// keeps paragraphs whole where possible, only oversized ones get cut by split_text
+pub fn pack_paragraphs(paragraphs: &[String], chunk_size: usize) -> Vec<String> {
+    let mut pages: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in paragraphs {
+        let extra = if current.is_empty() { 0 } else { 2 };
+        if current.chars().count() + extra + paragraph.chars().count() <= chunk_size {
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(paragraph);
+            continue;
+        }
+
+        if !current.is_empty() {
+            pages.push(std::mem::take(&mut current));
+        }
+
+        if paragraph.chars().count() > chunk_size {
+            let mut parts = split_text(paragraph, chunk_size);
+            current = parts.pop().unwrap_or_default();
+            pages.extend(parts);
+        } else {
+            current = paragraph.clone();
+        }
+    }
+
+    if !current.is_empty() {
+        pages.push(current);
+    }
+    pages
+}
+
 pub async fn is_admin_or_author(
     bot: &Bot,
     chat_id: ChatId,
@@ -35,6 +69,19 @@ pub async fn is_admin_or_author(
     false
 }
 
+// commands that change chat-wide state: anyone in private, only admins in groups
+pub async fn is_private_or_admin(bot: &Bot, msg: &Message) -> bool {
+    if msg.chat.is_private() {
+        return true;
+    }
+    let Some(user) = msg.from.as_ref() else {
+        return false;
+    };
+    bot.get_chat_member(msg.chat.id, user.id)
+        .await
+        .is_ok_and(|member| member.is_privileged())
+}
+
 pub fn is_author(clicker: &User, target_user_id: u64) -> bool {
     if target_user_id == 72 || clicker.id.0 == target_user_id {
         return true;
@@ -8,6 +8,7 @@ pub struct Paginator<'a, T> {
     bottom_rows: Vec<Vec<InlineKeyboardButton>>,
     callback_prefix: String,
     total_items: Option<usize>,
+    items_paged: bool, // items hold only the current page, e.g. when the database pages them
     callback_formatter: Option<Box<dyn Fn(usize) -> String + 'a>>,
 }
 
@@ -31,6 +32,7 @@ impl<'a> Paginator<'a, ()> {
             current_page: 0,
             bottom_rows: Vec::new(),
             callback_prefix: module_key.to_string(),
+            items_paged: false,
             callback_formatter: None,
         }
     }
@@ -46,6 +48,7 @@ impl<'a, T> Paginator<'a, T> {
             bottom_rows: Vec::new(),
             callback_prefix: module_key.to_string(),
             total_items: None,
+            items_paged: false,
             callback_formatter: None,
         }
     }
@@ -66,10 +69,13 @@ impl<'a, T> ItemsBuild<'a, T> for Paginator<'a, T> {
         let page = self.current_page.min(total_pages - 1);
 
         let mut keyboard: Vec<Vec<InlineKeyboardButton>> = if !self.items.is_empty() {
-            let start = page * self.per_page;
-            let end = (start + self.per_page).min(self.items.len());
-
-            let page_items = &self.items[start..end];
+            let page_items = if self.items_paged {
+                &self.items[..self.items.len().min(self.per_page)]
+            } else {
+                let start = (page * self.per_page).min(self.items.len());
+                let end = (start + self.per_page).min(self.items.len());
+                &self.items[start..end]
+            };
 
             page_items
                 .iter()
@@ -166,4 +172,9 @@ impl<'a, T> Paginator<'a, T> {
         self.total_items = Some(total);
         self
     }
+
+    pub fn set_items_paged(mut self) -> Self {
+        self.items_paged = true;
+        self
+    }
 }